@@ -0,0 +1,275 @@
+use crate::*;
+
+/// Evolves two or more populations whose fitness is only defined relative to
+/// each other (e.g. predators and prey).
+///
+/// Every individual is scored against a sample of opponents drawn from the
+/// other populations and from their halls of fame - the champions of the
+/// previous generations - and its fitness is the average of those scores.
+pub struct CoevolutionAlgorithm<S> {
+    ga: GeneticAlgorithm<S>,
+    opponents: usize,
+    hall_of_fame_opponents: usize,
+    hall_of_fame: HallOfFame,
+    halls_of_fame: Vec<HallOfFame>,
+}
+
+impl<S> CoevolutionAlgorithm<S>
+where
+    S: SelectionMethod,
+{
+    /// `hall_of_fame` is used as a template - each population gets its own
+    /// copy, into which the champion of every generation is inserted.
+    pub fn new(
+        ga: GeneticAlgorithm<S>,
+        opponents: usize,
+        hall_of_fame_opponents: usize,
+        hall_of_fame: HallOfFame,
+    ) -> Self {
+        assert!(opponents > 0);
+
+        Self {
+            ga,
+            opponents,
+            hall_of_fame_opponents,
+            hall_of_fame,
+            halls_of_fame: Vec::new(),
+        }
+    }
+
+    pub fn hall_of_fame(&self, population: usize) -> Option<&HallOfFame> {
+        self.halls_of_fame.get(population)
+    }
+
+    /// Evaluates and evolves all the populations.
+    ///
+    /// `evaluate` is called as `evaluate(subject_population, subject,
+    /// opponent_population, opponent)` and should return the subject's
+    /// (non-negative) score in that encounter.
+    pub fn evolve<I>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        populations: &[Vec<I>],
+        mut evaluate: impl FnMut(usize, &I, usize, &I) -> f32,
+    ) -> (Vec<Vec<I>>, Vec<Statistics>)
+    where
+        I: Individual,
+    {
+        assert!(populations.len() > 1);
+        assert!(populations.iter().all(|population| !population.is_empty()));
+
+        self.halls_of_fame
+            .resize_with(populations.len(), || self.hall_of_fame.clone());
+
+        let champions: Vec<Vec<I>> = self
+            .halls_of_fame
+            .iter()
+            .map(|hall_of_fame| {
+                hall_of_fame
+                    .entries()
+                    .iter()
+                    .map(|entry| I::create(entry.chromosome().clone()))
+                    .collect()
+            })
+            .collect();
+
+        populations
+            .iter()
+            .enumerate()
+            .map(|(subject_population, population)| {
+                let scored: Vec<_> = population
+                    .iter()
+                    .map(|subject| {
+                        let mut score = 0.0;
+                        let mut encounters = 0;
+
+                        for _ in 0..self.opponents {
+                            let opponent_population = Self::pick_opponent_population(
+                                rng,
+                                populations,
+                                subject_population,
+                            );
+
+                            let opponent = populations[opponent_population]
+                                .choose(rng)
+                                .expect("got an empty population");
+
+                            score += evaluate(
+                                subject_population,
+                                subject,
+                                opponent_population,
+                                opponent,
+                            );
+                            encounters += 1;
+                        }
+
+                        for _ in 0..self.hall_of_fame_opponents {
+                            let opponent_population = Self::pick_opponent_population(
+                                rng,
+                                populations,
+                                subject_population,
+                            );
+
+                            if let Some(opponent) = champions[opponent_population].choose(rng) {
+                                score += evaluate(
+                                    subject_population,
+                                    subject,
+                                    opponent_population,
+                                    opponent,
+                                );
+                                encounters += 1;
+                            }
+                        }
+
+                        ScoredIndividual {
+                            chromosome: subject.chromosome().clone(),
+                            fitness: score / encounters as f32,
+                        }
+                    })
+                    .collect();
+
+                let champion = scored
+                    .iter()
+                    .max_by(|a, b| a.fitness.total_cmp(&b.fitness))
+                    .expect("got an empty population");
+
+                self.halls_of_fame[subject_population]
+                    .insert(&champion.chromosome, champion.fitness);
+
+                let (evolved, stats) = self.ga.evolve(rng, &scored);
+
+                let evolved = evolved
+                    .into_iter()
+                    .map(|individual| I::create(individual.chromosome))
+                    .collect();
+
+                (evolved, stats)
+            })
+            .unzip()
+    }
+
+    fn pick_opponent_population(
+        rng: &mut dyn RngCore,
+        populations: &[Vec<impl Individual>],
+        subject_population: usize,
+    ) -> usize {
+        let population = rng.gen_range(0..populations.len() - 1);
+
+        if population >= subject_population {
+            population + 1
+        } else {
+            population
+        }
+    }
+}
+
+/// Adapts a coevolved individual to `GeneticAlgorithm`, which expects the
+/// fitness to be known upfront.
+struct ScoredIndividual {
+    chromosome: Chromosome,
+    fitness: f32,
+}
+
+impl Individual for ScoredIndividual {
+    fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    fn chromosome(&self) -> &Chromosome {
+        &self.chromosome
+    }
+
+    fn create(chromosome: Chromosome) -> Self {
+        Self {
+            chromosome,
+            fitness: 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn individual(genes: &[f32]) -> TestIndividual {
+        TestIndividual::create(genes.iter().cloned().collect())
+    }
+
+    fn coevolution() -> CoevolutionAlgorithm<RouletteWheelSelection> {
+        CoevolutionAlgorithm::new(
+            GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.5, 0.5),
+            ),
+            2,
+            1,
+            HallOfFame::new(3, 0.0),
+        )
+    }
+
+    fn populations() -> Vec<Vec<TestIndividual>> {
+        vec![
+            vec![individual(&[1.0, 2.0]), individual(&[3.0, 4.0])],
+            vec![
+                individual(&[0.0, 1.0]),
+                individual(&[2.0, 2.0]),
+                individual(&[5.0, 0.0]),
+            ],
+        ]
+    }
+
+    #[test]
+    fn evaluates_subjects_against_other_populations() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut coevolution = coevolution();
+        let mut encounters = Vec::new();
+
+        let (populations, stats) = coevolution.evolve(&mut rng, &populations(), |sp, _, op, _| {
+            encounters.push((sp, op));
+            1.0
+        });
+
+        assert_eq!(populations.len(), 2);
+        assert_eq!(populations[0].len(), 2);
+        assert_eq!(populations[1].len(), 3);
+        assert_eq!(stats.len(), 2);
+
+        // hall of fame is empty during the first generation, so there are
+        // exactly two encounters per individual
+        assert_eq!(encounters.len(), 2 * (2 + 3));
+        assert!(encounters.iter().all(|(sp, op)| sp != op));
+    }
+
+    #[test]
+    fn records_champions_in_hall_of_fame() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut coevolution = coevolution();
+
+        let score = |_: usize, subject: &TestIndividual, _: usize, _: &TestIndividual| {
+            subject.fitness().max(0.0)
+        };
+
+        let (populations, _) = coevolution.evolve(&mut rng, &populations(), score);
+
+        let best = |population| {
+            coevolution
+                .hall_of_fame(population)
+                .unwrap()
+                .best()
+                .unwrap()
+                .chromosome()
+        };
+
+        assert_eq!(best(0), individual(&[3.0, 4.0]).chromosome());
+        assert_eq!(best(1), individual(&[5.0, 0.0]).chromosome());
+
+        coevolution.evolve(&mut rng, &populations, score);
+
+        assert_eq!(coevolution.hall_of_fame(0).unwrap().len(), 2);
+        assert_eq!(coevolution.hall_of_fame(1).unwrap().len(), 2);
+        assert!(coevolution.hall_of_fame(2).is_none());
+    }
+}
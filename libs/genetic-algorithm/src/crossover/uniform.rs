@@ -27,6 +27,7 @@ impl CrossOverMethod for UniformCrossover {
 }
 
 #[cfg(test)]
+#[allow(clippy::default_constructed_unit_structs)]
 mod test {
     use super::*;
     use rand::SeedableRng;
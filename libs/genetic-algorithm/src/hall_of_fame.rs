@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// Archive of the best distinct individuals seen across all generations.
+///
+/// Two chromosomes are considered the same individual when the euclidean
+/// distance between them is not greater than `distance_threshold`; in that
+/// case only the fitter one is kept.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HallOfFame {
+    capacity: usize,
+    distance_threshold: f32,
+    entries: Vec<HallOfFameEntry>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HallOfFameEntry {
+    chromosome: Chromosome,
+    fitness: f32,
+}
+
+impl HallOfFame {
+    pub fn new(capacity: usize, distance_threshold: f32) -> Self {
+        assert!(capacity > 0);
+        assert!(distance_threshold >= 0.0);
+
+        Self {
+            capacity,
+            distance_threshold,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn distance_threshold(&self) -> f32 {
+        self.distance_threshold
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries ordered from the fittest one.
+    pub fn entries(&self) -> &[HallOfFameEntry] {
+        &self.entries
+    }
+
+    pub fn best(&self) -> Option<&HallOfFameEntry> {
+        self.entries.first()
+    }
+
+    pub fn update<I>(&mut self, population: &[I])
+    where
+        I: Individual,
+    {
+        for individual in population {
+            self.insert(individual.chromosome(), individual.fitness());
+        }
+    }
+
+    /// Returns whether the chromosome made it into the hall of fame.
+    pub fn insert(&mut self, chromosome: &Chromosome, fitness: f32) -> bool {
+        if self.entries.len() == self.capacity
+            && self
+                .entries
+                .last()
+                .is_some_and(|worst| worst.fitness >= fitness)
+        {
+            return false;
+        }
+
+        let is_similar = |entry: &HallOfFameEntry| {
+            Self::distance(&entry.chromosome, chromosome) <= self.distance_threshold
+        };
+
+        if self
+            .entries
+            .iter()
+            .any(|entry| is_similar(entry) && entry.fitness >= fitness)
+        {
+            return false;
+        }
+
+        self.entries.retain(|entry| !is_similar(entry));
+
+        let position = self
+            .entries
+            .iter()
+            .position(|entry| entry.fitness < fitness)
+            .unwrap_or(self.entries.len());
+
+        self.entries.insert(
+            position,
+            HallOfFameEntry {
+                chromosome: chromosome.clone(),
+                fitness,
+            },
+        );
+
+        self.entries.truncate(self.capacity);
+
+        true
+    }
+
+    fn distance(a: &Chromosome, b: &Chromosome) -> f32 {
+        if a.len() != b.len() {
+            return f32::INFINITY;
+        }
+
+        a.iter()
+            .zip(b.iter())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    }
+}
+
+impl HallOfFameEntry {
+    pub fn chromosome(&self) -> &Chromosome {
+        &self.chromosome
+    }
+
+    pub fn fitness(&self) -> f32 {
+        self.fitness
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chromosome(genes: &[f32]) -> Chromosome {
+        genes.iter().cloned().collect()
+    }
+
+    fn fitnesses(hall_of_fame: &HallOfFame) -> Vec<f32> {
+        hall_of_fame
+            .entries()
+            .iter()
+            .map(HallOfFameEntry::fitness)
+            .collect()
+    }
+
+    mod insert {
+        use super::*;
+
+        #[test]
+        fn keeps_the_best_entries_in_order() {
+            let mut hall_of_fame = HallOfFame::new(3, 0.0);
+
+            for (n, fitness) in [2.0, 5.0, 1.0, 4.0, 3.0].into_iter().enumerate() {
+                hall_of_fame.insert(&chromosome(&[n as f32]), fitness);
+            }
+
+            assert_eq!(fitnesses(&hall_of_fame), vec![5.0, 4.0, 3.0]);
+            assert_eq!(
+                hall_of_fame.best().unwrap().chromosome(),
+                &chromosome(&[1.0])
+            );
+        }
+
+        #[test]
+        fn rejects_worse_similar_chromosomes() {
+            let mut hall_of_fame = HallOfFame::new(3, 0.5);
+
+            assert!(hall_of_fame.insert(&chromosome(&[1.0, 1.0]), 2.0));
+            assert!(!hall_of_fame.insert(&chromosome(&[1.0, 1.3]), 1.0));
+            assert!(hall_of_fame.insert(&chromosome(&[1.0, 2.0]), 1.0));
+
+            assert_eq!(fitnesses(&hall_of_fame), vec![2.0, 1.0]);
+        }
+
+        #[test]
+        fn replaces_worse_similar_chromosomes() {
+            let mut hall_of_fame = HallOfFame::new(3, 0.5);
+
+            hall_of_fame.insert(&chromosome(&[1.0, 1.0]), 2.0);
+            hall_of_fame.insert(&chromosome(&[5.0, 5.0]), 1.0);
+
+            assert!(hall_of_fame.insert(&chromosome(&[1.0, 1.3]), 3.0));
+
+            assert_eq!(fitnesses(&hall_of_fame), vec![3.0, 1.0]);
+            assert_eq!(
+                hall_of_fame.best().unwrap().chromosome(),
+                &chromosome(&[1.0, 1.3])
+            );
+        }
+    }
+
+    mod update {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut hall_of_fame = HallOfFame::new(2, 0.0);
+
+            hall_of_fame.update(&[
+                TestIndividual::create(chromosome(&[1.0, 1.0])),
+                TestIndividual::create(chromosome(&[3.0, 2.0])),
+                TestIndividual::create(chromosome(&[0.5, 0.5])),
+            ]);
+
+            assert_eq!(fitnesses(&hall_of_fame), vec![5.0, 2.0]);
+        }
+    }
+
+    mod serde {
+        use super::*;
+
+        #[test]
+        fn round_trips_through_json() {
+            let mut hall_of_fame = HallOfFame::new(2, 0.1);
+            hall_of_fame.insert(&chromosome(&[1.0, 2.0]), 3.0);
+
+            let json = serde_json::to_string(&hall_of_fame).unwrap();
+            let restored: HallOfFame = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(restored.capacity(), 2);
+            assert_eq!(restored.entries()[0].chromosome(), &chromosome(&[1.0, 2.0]));
+            assert_eq!(fitnesses(&restored), vec![3.0]);
+        }
+    }
+}
@@ -31,7 +31,7 @@ impl Individual for TestIndividual {
 
     fn chromosome(&self) -> &Chromosome {
         match self {
-            Self::WithChromosome { chromosome } => &chromosome,
+            Self::WithChromosome { chromosome } => chromosome,
             Self::WithFitness { .. } => panic!("not supported for TestIndividual"),
         }
     }
@@ -1,14 +1,17 @@
 #![feature(impl_trait_in_assoc_type)]
 
-use std::marker::PhantomPinned;
-
-pub use self::{chromosome::*, crossover::*, individual::*, mutation::*, selection::*};
+pub use self::{
+    chromosome::*, coevolution::*, crossover::*, hall_of_fame::*, individual::*, mutation::*,
+    selection::*,
+};
 
 use rand::seq::SliceRandom;
 use rand::{Rng, RngCore};
 
 mod chromosome;
+mod coevolution;
 mod crossover;
+mod hall_of_fame;
 mod individual;
 mod mutation;
 mod selection;
@@ -23,6 +26,7 @@ pub struct GeneticAlgorithm<S> {
     selection_method: S,
     crossover_method: Box<dyn CrossOverMethod>,
     mutation_method: Box<dyn MutationMethod>,
+    hall_of_fame: Option<HallOfFame>,
 }
 
 impl<S> GeneticAlgorithm<S>
@@ -38,10 +42,24 @@ where
             selection_method,
             crossover_method: Box::new(crossover_method),
             mutation_method: Box::new(mutation_method),
+            hall_of_fame: None,
         }
     }
 
-    pub fn evolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    /// Makes `evolve` record the best individuals of every population it
+    /// sees into `hall_of_fame`.
+    pub fn with_hall_of_fame(mut self, hall_of_fame: HallOfFame) -> Self {
+        self.hall_of_fame = Some(hall_of_fame);
+        self
+    }
+
+    pub fn hall_of_fame(&self) -> Option<&HallOfFame> {
+        self.hall_of_fame.as_ref()
+    }
+
+    /// Draws from `rng` in a fixed order, so evolving the same population
+    /// with identically seeded RNGs yields identical offspring.
+    pub fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
     where
         I: Individual,
     {
@@ -49,9 +67,9 @@ where
 
         let new_population = (0..population.len())
             .map(|_| {
-                let parent_a = self.selection_method.select(rng, &population).chromosome();
+                let parent_a = self.selection_method.select(rng, population).chromosome();
 
-                let parent_b = self.selection_method.select(rng, &population).chromosome();
+                let parent_b = self.selection_method.select(rng, population).chromosome();
 
                 let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
 
@@ -62,6 +80,10 @@ where
 
         let stats = Statistics::new(population);
 
+        if let Some(hall_of_fame) = &mut self.hall_of_fame {
+            hall_of_fame.update(population);
+        }
+
         (new_population, stats)
     }
 }
@@ -104,6 +126,7 @@ impl Statistics {
 }
 
 #[cfg(test)]
+#[allow(clippy::default_constructed_unit_structs)]
 mod tests {
     use super::*;
     use rand::SeedableRng;
@@ -118,7 +141,7 @@ mod tests {
     fn test() {
         let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-        let ga = GeneticAlgorithm::new(
+        let mut ga = GeneticAlgorithm::new(
             RouletteWheelSelection::default(),
             UniformCrossover::default(),
             GaussianMutation::new(0.5, 0.5),
@@ -144,4 +167,32 @@ mod tests {
 
         assert_eq!(population, expected_population);
     }
+
+    #[test]
+    fn updates_hall_of_fame() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut ga = GeneticAlgorithm::new(
+            RouletteWheelSelection::new(),
+            UniformCrossover::new(),
+            GaussianMutation::new(0.5, 0.5),
+        )
+        .with_hall_of_fame(HallOfFame::new(2, 0.0));
+
+        let population = vec![
+            individual(&[0.0, 0.0, 0.0]),
+            individual(&[1.0, 2.0, 4.0]),
+            individual(&[1.0, 1.0, 1.0]),
+        ];
+
+        ga.evolve(&mut rng, &population);
+
+        let hall_of_fame = ga.hall_of_fame().unwrap();
+
+        assert_eq!(hall_of_fame.len(), 2);
+        assert_eq!(
+            hall_of_fame.best().unwrap().chromosome(),
+            population[1].chromosome()
+        );
+    }
 }
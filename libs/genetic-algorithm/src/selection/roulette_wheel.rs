@@ -25,7 +25,6 @@ mod test {
     use std::collections::BTreeMap;
 
     use super::*;
-    use maplit;
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
 
@@ -0,0 +1,42 @@
+//! Compares the scalar and SIMD propagation paths:
+//!
+//! ```text
+//! cargo bench -p neural-network --bench propagate -- --save-baseline scalar
+//! cargo bench -p neural-network --bench propagate --features simd -- --baseline scalar
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use neural_network::{layer_topology::LayerTopology, Network, NetworkScratch};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+fn propagate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("propagate");
+
+    // (9, 18, 2) is what `simulation::Brain` uses with the default eye
+    for sizes in [[9, 18, 2], [32, 64, 2], [128, 256, 16], [512, 1024, 64]] {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let topology: Vec<_> = sizes.iter().map(|&n| LayerTopology::new(n)).collect();
+        let mut network = Network::random(&mut rng, &topology);
+        let mut scratch = NetworkScratch::new(&topology);
+
+        let inputs: Vec<f32> = (0..sizes[0]).map(|_| rng.gen()).collect();
+
+        let id = BenchmarkId::from_parameter(format!("{}-{}-{}", sizes[0], sizes[1], sizes[2]));
+
+        group.bench_with_input(id, &inputs, |b, inputs| {
+            b.iter(|| {
+                network
+                    .propagate_into(black_box(inputs), &mut scratch)
+                    .first()
+                    .copied()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, propagate);
+criterion_main!(benches);
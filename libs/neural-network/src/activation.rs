@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+
+/// Non-linearity applied to a layer's outputs.
+///
+/// `exp`, `tanh` and the like come from `libm` rather than the platform's
+/// math library, so outputs are the same on every target.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Activation {
+    #[default]
+    ReLU,
+    /// ReLU with the given slope for negative inputs.
+    LeakyReLU(f32),
+    Sigmoid,
+    Tanh,
+    Identity,
+    Softsign,
+    /// 1.0 for positive inputs, 0.0 otherwise.
+    Step,
+    Sin,
+    Gaussian,
+}
+
+impl Activation {
+    pub fn apply(self, x: f32) -> f32 {
+        match self {
+            Self::ReLU => x.max(0.0),
+            Self::LeakyReLU(slope) => {
+                if x > 0.0 {
+                    x
+                } else {
+                    slope * x
+                }
+            }
+            Self::Sigmoid => 1.0 / (1.0 + libm::expf(-x)),
+            Self::Tanh => libm::tanhf(x),
+            Self::Identity => x,
+            Self::Softsign => x / (1.0 + x.abs()),
+            Self::Step => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Self::Sin => libm::sinf(x),
+            Self::Gaussian => libm::expf(-x * x),
+        }
+    }
+
+    /// Derivative at `x`; for the piecewise activations, the derivative at
+    /// the kink is taken from the left.
+    pub fn derivative(self, x: f32) -> f32 {
+        match self {
+            Self::ReLU => Self::Step.apply(x),
+            Self::LeakyReLU(slope) => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    slope
+                }
+            }
+            Self::Sigmoid => {
+                let y = self.apply(x);
+                y * (1.0 - y)
+            }
+            Self::Tanh => 1.0 - libm::tanhf(x).powi(2),
+            Self::Identity => 1.0,
+            Self::Softsign => 1.0 / (1.0 + x.abs()).powi(2),
+            Self::Step => 0.0,
+            Self::Sin => libm::cosf(x),
+            Self::Gaussian => -2.0 * x * self.apply(x),
+        }
+    }
+
+    /// Applies the activation to all the values in place.
+    pub fn apply_all(self, values: &mut [f32]) {
+        #[cfg(feature = "simd")]
+        let values = self.apply_all_simd(values);
+
+        for value in values {
+            *value = self.apply(*value);
+        }
+    }
+
+    /// Vectorizes the piecewise-linear activations and returns the remainder
+    /// that still has to be processed one by one.
+    #[cfg(feature = "simd")]
+    fn apply_all_simd(self, values: &mut [f32]) -> &mut [f32] {
+        use crate::math::LANES;
+        use std::simd::{cmp::SimdPartialOrd, num::SimdFloat, Select, Simd};
+
+        if !matches!(
+            self,
+            Self::ReLU | Self::LeakyReLU(_) | Self::Identity | Self::Softsign | Self::Step
+        ) {
+            return values;
+        }
+
+        let zero = Simd::<f32, LANES>::splat(0.0);
+        let one = Simd::<f32, LANES>::splat(1.0);
+
+        let (chunks, rest) = values.as_chunks_mut::<LANES>();
+
+        for chunk in chunks {
+            let x = Simd::from_array(*chunk);
+
+            let y = match self {
+                Self::ReLU => x.simd_max(zero),
+                Self::LeakyReLU(slope) => x.simd_gt(zero).select(x, x * Simd::splat(slope)),
+                Self::Identity => x,
+                Self::Softsign => x / (one + x.abs()),
+                Self::Step => x.simd_gt(zero).select(one, zero),
+                _ => unreachable!(),
+            };
+
+            *chunk = y.to_array();
+        }
+
+        rest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    const INPUTS: [f32; 4] = [-2.0, -0.5, 0.0, 1.5];
+
+    fn assert_applies(activation: Activation, expected: [f32; 4]) {
+        let actual: Vec<_> = INPUTS.iter().map(|&x| activation.apply(x)).collect();
+
+        assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn apply_all() {
+        let mut values: Vec<_> = (-10..10).map(|n| n as f32 / 4.0).collect();
+        let expected: Vec<_> = values
+            .iter()
+            .map(|&x| Activation::Softsign.apply(x))
+            .collect();
+
+        Activation::Softsign.apply_all(&mut values);
+
+        assert_relative_eq!(values.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn derivative() {
+        let activations = [
+            Activation::LeakyReLU(0.1),
+            Activation::Sigmoid,
+            Activation::Tanh,
+            Activation::Identity,
+            Activation::Softsign,
+            Activation::Sin,
+            Activation::Gaussian,
+        ];
+
+        for activation in activations {
+            for x in [-2.0, -0.5, 0.25, 1.5] {
+                let h = 1e-2;
+                let expected = (activation.apply(x + h) - activation.apply(x - h)) / (2.0 * h);
+
+                assert_relative_eq!(activation.derivative(x), expected, epsilon = 1e-3);
+            }
+        }
+
+        assert_eq!(Activation::ReLU.derivative(-1.0), 0.0);
+        assert_eq!(Activation::ReLU.derivative(1.0), 1.0);
+        assert_eq!(Activation::Step.derivative(1.0), 0.0);
+    }
+
+    #[test]
+    fn relu() {
+        assert_applies(Activation::ReLU, [0.0, 0.0, 0.0, 1.5]);
+    }
+
+    #[test]
+    fn leaky_relu() {
+        assert_applies(Activation::LeakyReLU(0.1), [-0.2, -0.05, 0.0, 1.5]);
+    }
+
+    #[test]
+    fn sigmoid() {
+        assert_applies(
+            Activation::Sigmoid,
+            [0.11920292, 0.37754067, 0.5, 0.8175745],
+        );
+    }
+
+    #[test]
+    fn tanh() {
+        assert_applies(Activation::Tanh, [-0.9640276, -0.46211716, 0.0, 0.9051482]);
+    }
+
+    #[test]
+    fn identity() {
+        assert_applies(Activation::Identity, INPUTS);
+    }
+
+    #[test]
+    fn softsign() {
+        assert_applies(Activation::Softsign, [-0.6666667, -0.33333334, 0.0, 0.6]);
+    }
+
+    #[test]
+    fn step() {
+        assert_applies(Activation::Step, [0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn sin() {
+        assert_applies(Activation::Sin, [-0.9092974, -0.47942555, 0.0, 0.997495]);
+    }
+
+    #[test]
+    fn gaussian() {
+        assert_applies(
+            Activation::Gaussian,
+            [0.01831564, 0.7788008, 1.0, 0.10539922],
+        );
+    }
+}
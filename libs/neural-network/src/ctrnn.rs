@@ -0,0 +1,388 @@
+use crate::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CtrnnTopology {
+    pub inputs: usize,
+    pub neurons: usize,
+    /// Number of neurons (the first ones) whose firing rates make up the
+    /// network's outputs.
+    pub outputs: usize,
+    /// Integration step, in the same units as the time constants.
+    pub timestep: f32,
+}
+
+impl CtrnnTopology {
+    pub fn new(inputs: usize, neurons: usize, outputs: usize) -> Self {
+        Self {
+            inputs,
+            neurons,
+            outputs,
+            timestep: 0.1,
+        }
+    }
+
+    pub fn with_timestep(mut self, timestep: f32) -> Self {
+        self.timestep = timestep;
+        self
+    }
+}
+
+/// Continuous-time recurrent neural network.
+///
+/// Every neuron has a state `y`, which evolves as:
+///
+/// ```text
+/// tau * dy/dt = -y + sum(w * sigmoid(gain * (y' + bias))) + sum(v * input)
+/// ```
+///
+/// ... and is integrated using Euler's method, one `timestep` per
+/// `propagate` call.
+///
+/// Time constants are taken by their absolute value and clamped to at least
+/// `timestep`, so that no set of weights can make the integration unstable.
+#[derive(Clone, Debug)]
+pub struct Ctrnn {
+    topology: CtrnnTopology,
+    biases: Vec<f32>,
+    time_constants: Vec<f32>,
+    gains: Vec<f32>,
+    input_weights: Vec<f32>,
+    weights: Vec<f32>,
+    states: Vec<f32>,
+    rates: Vec<f32>,
+}
+
+impl Ctrnn {
+    /// Draws from `rng` in the same order as `from_weights` consumes the
+    /// weights, so the same seed always yields the same network.
+    pub fn random(rng: &mut dyn RngCore, topology: CtrnnTopology) -> Self {
+        Self::from_weights(
+            topology,
+            std::iter::repeat_with(|| rng.gen_range(-1.0..=1.0)).take(Self::weight_count(topology)),
+        )
+    }
+
+    /// Number of parameters a network of given topology has; panics when it
+    /// doesn't fit in `usize`.
+    pub fn weight_count(topology: CtrnnTopology) -> usize {
+        Self::checked_weight_count(topology).expect("too many parameters")
+    }
+
+    fn checked_weight_count(topology: CtrnnTopology) -> Option<usize> {
+        topology
+            .inputs
+            .checked_add(topology.neurons)?
+            .checked_add(3)?
+            .checked_mul(topology.neurons)
+    }
+
+    /// Consumes the parameters neuron by neuron: bias, time constant, gain,
+    /// input weights and then weights of the connections from all the
+    /// neurons (including itself).
+    pub fn from_weights(topology: CtrnnTopology, weights: impl IntoIterator<Item = f32>) -> Self {
+        Self::try_from_weights(topology, weights).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_from_weights(
+        topology: CtrnnTopology,
+        weights: impl IntoIterator<Item = f32>,
+    ) -> Result<Self, Error> {
+        let CtrnnTopology {
+            inputs,
+            neurons,
+            outputs,
+            timestep,
+        } = topology;
+
+        let reason = if neurons == 0 {
+            Some("expected at least one neuron")
+        } else if outputs > neurons {
+            Some("expected at most as many outputs as there are neurons")
+        } else if timestep.is_nan() || timestep <= 0.0 {
+            Some("expected a positive timestep")
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            return Err(Error::InvalidCtrnn { reason });
+        }
+
+        let expected = Self::checked_weight_count(topology).ok_or(Error::InvalidCtrnn {
+            reason: "too many parameters",
+        })?;
+
+        let weights: Vec<_> = weights.into_iter().collect();
+
+        if weights.len() != expected {
+            return Err(Error::WeightCountMismatch {
+                expected,
+                actual: weights.len(),
+            });
+        }
+
+        let mut weights = weights.into_iter();
+        let mut next = || weights.next().unwrap();
+
+        let mut biases = Vec::with_capacity(neurons);
+        let mut time_constants = Vec::with_capacity(neurons);
+        let mut gains = Vec::with_capacity(neurons);
+        let mut input_weights = Vec::with_capacity(neurons * inputs);
+        let mut recurrent_weights = Vec::with_capacity(neurons * neurons);
+
+        for _ in 0..neurons {
+            biases.push(next());
+            time_constants.push(next());
+            gains.push(next());
+
+            for _ in 0..inputs {
+                input_weights.push(next());
+            }
+
+            for _ in 0..neurons {
+                recurrent_weights.push(next());
+            }
+        }
+
+        Ok(Self {
+            topology,
+            biases,
+            time_constants,
+            gains,
+            input_weights,
+            weights: recurrent_weights,
+            states: vec![0.0; neurons],
+            rates: vec![0.0; neurons],
+        })
+    }
+
+    pub fn topology(&self) -> CtrnnTopology {
+        self.topology
+    }
+
+    pub fn states(&self) -> &[f32] {
+        &self.states
+    }
+
+    pub fn reset(&mut self) {
+        self.states.fill(0.0);
+    }
+
+    /// Advances the network by a single `timestep` and returns the firing
+    /// rates of its output neurons.
+    pub fn propagate(&mut self, inputs: &[f32]) -> Vec<f32> {
+        let mut outputs = vec![0.0; self.topology.outputs];
+        self.propagate_into(inputs, &mut outputs);
+        outputs
+    }
+
+    pub fn propagate_into(&mut self, inputs: &[f32], outputs: &mut [f32]) {
+        let CtrnnTopology {
+            inputs: input_size,
+            neurons,
+            outputs: output_size,
+            timestep,
+        } = self.topology;
+
+        assert_eq!(inputs.len(), input_size);
+        assert_eq!(outputs.len(), output_size);
+
+        for neuron in 0..neurons {
+            self.rates[neuron] = self.rate(neuron);
+        }
+
+        for neuron in 0..neurons {
+            let time_constant = self.time_constants[neuron].abs().max(timestep);
+
+            let input = math::dot(
+                inputs,
+                &self.input_weights[neuron * input_size..][..input_size],
+            );
+
+            let recurrent = math::dot(&self.rates, &self.weights[neuron * neurons..][..neurons]);
+            let state = &mut self.states[neuron];
+
+            *state += timestep / time_constant * (-*state + recurrent + input);
+        }
+
+        for (neuron, output) in outputs.iter_mut().enumerate() {
+            *output = self.rate(neuron);
+        }
+    }
+
+    fn rate(&self, neuron: usize) -> f32 {
+        Activation::Sigmoid.apply(self.gains[neuron] * (self.states[neuron] + self.biases[neuron]))
+    }
+
+    pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        let inputs = self.topology.inputs;
+        let neurons = self.topology.neurons;
+
+        (0..neurons)
+            .flat_map(move |neuron| {
+                [
+                    &self.biases[neuron],
+                    &self.time_constants[neuron],
+                    &self.gains[neuron],
+                ]
+                .into_iter()
+                .chain(&self.input_weights[neuron * inputs..][..inputs])
+                .chain(&self.weights[neuron * neurons..][..neurons])
+            })
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn sigmoid(x: f32) -> f32 {
+        1.0 / (1.0 + (-x).exp())
+    }
+
+    mod random {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let topology = CtrnnTopology::new(2, 3, 1);
+
+            let network = |seed| {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                Ctrnn::random(&mut rng, topology)
+                    .weights()
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(network(0).len(), 3 * (3 + 2 + 3));
+            assert_eq!(network(0), network(0));
+            assert_ne!(network(0), network(1));
+        }
+    }
+
+    mod propagate {
+        use super::*;
+
+        /// Single neuron, driven by a single input and exciting itself.
+        fn network(time_constant: f32) -> Ctrnn {
+            Ctrnn::from_weights(
+                CtrnnTopology::new(1, 1, 1).with_timestep(0.5),
+                [0.2, time_constant, 2.0, 1.0, 0.5],
+            )
+        }
+
+        #[test]
+        fn integrates_states() {
+            let mut network = network(2.0);
+            let mut state = 0.0;
+
+            for input in [1.0, 1.0, -1.0] {
+                let outputs = network.propagate(&[input]);
+
+                let rate = sigmoid(2.0 * (state + 0.2));
+                state += 0.5 / 2.0 * (-state + 0.5 * rate + input);
+
+                assert_relative_eq!(network.states()[0], state);
+                assert_relative_eq!(outputs[0], sigmoid(2.0 * (state + 0.2)));
+            }
+        }
+
+        #[test]
+        fn clamps_time_constants() {
+            let mut actual = network(-0.1);
+            let mut expected = network(0.5);
+
+            for _ in 0..3 {
+                assert_eq!(actual.propagate(&[1.0]), expected.propagate(&[1.0]));
+            }
+        }
+
+        #[test]
+        fn forgets_after_reset() {
+            let mut network = network(2.0);
+
+            let first = network.propagate(&[1.0]);
+            network.propagate(&[1.0]);
+            network.reset();
+
+            assert_eq!(network.propagate(&[1.0]), first);
+        }
+    }
+
+    mod weights {
+        use super::*;
+
+        #[test]
+        fn round_trips_through_from_weights() {
+            let weights: Vec<_> = (0..2 * (3 + 1 + 2)).map(|n| n as f32).collect();
+            let network = Ctrnn::from_weights(CtrnnTopology::new(1, 2, 1), weights.clone());
+
+            assert_eq!(network.weights().collect::<Vec<_>>(), weights);
+        }
+    }
+
+    mod try_from_weights {
+        use super::*;
+
+        #[test]
+        fn rejects_wrong_weight_counts() {
+            let topology = CtrnnTopology::new(1, 2, 1);
+
+            assert_eq!(Ctrnn::weight_count(topology), 2 * (3 + 1 + 2));
+
+            for actual in [11, 13] {
+                assert_eq!(
+                    Ctrnn::try_from_weights(topology, vec![0.0; actual]).unwrap_err(),
+                    Error::WeightCountMismatch {
+                        expected: 12,
+                        actual
+                    }
+                );
+            }
+        }
+
+        #[test]
+        fn rejects_invalid_topologies() {
+            for (topology, reason) in [
+                (CtrnnTopology::new(1, 0, 0), "expected at least one neuron"),
+                (
+                    CtrnnTopology::new(1, 2, 3),
+                    "expected at most as many outputs as there are neurons",
+                ),
+                (
+                    CtrnnTopology::new(1, 1, 1).with_timestep(0.0),
+                    "expected a positive timestep",
+                ),
+                (
+                    CtrnnTopology::new(1, 1, 1).with_timestep(f32::NAN),
+                    "expected a positive timestep",
+                ),
+            ] {
+                let weights = vec![0.0; Ctrnn::weight_count(topology)];
+
+                assert_eq!(
+                    Ctrnn::try_from_weights(topology, weights).unwrap_err(),
+                    Error::InvalidCtrnn { reason }
+                );
+            }
+
+            assert_eq!(
+                Ctrnn::try_from_weights(CtrnnTopology::new(1, usize::MAX / 2, 1), Vec::new())
+                    .unwrap_err(),
+                Error::InvalidCtrnn {
+                    reason: "too many parameters"
+                }
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "got too many weights")]
+        fn panics_on_leftover_weights() {
+            Ctrnn::from_weights(CtrnnTopology::new(1, 1, 1), vec![0.0; 6]);
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use crate::*;
+
+/// Networks are equal when they share the topology and all the weights;
+/// the recurrent state is not compared.
+impl PartialEq for Network {
+    fn eq(&self, other: &Self) -> bool {
+        self.same_topology(other) && self.weights().eq(other.weights())
+    }
+}
+
+/// What changed between two networks of the same topology - see
+/// `Network::diff`.
+///
+/// Layers are listed without the input layer, i.e. `layers[0]` is the first
+/// hidden layer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkDiff {
+    pub layers: Vec<LayerDiff>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerDiff {
+    /// Number of weights (biases included) that changed.
+    pub changed: usize,
+    /// L2 distance between the layer's weights.
+    pub distance: f32,
+    /// Only the neurons whose weights changed, in order.
+    pub neurons: Vec<NeuronDiff>,
+}
+
+/// Changes of a single neuron's weights, including its bias, its gates'
+/// weights and (for plastic layers) its rules.
+///
+/// For convolutions, `neuron` is the channel whose kernel changed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuronDiff {
+    pub neuron: usize,
+    pub changed: usize,
+    /// Largest change (by magnitude) among the neuron's weights.
+    pub max_change: f32,
+}
+
+impl NetworkDiff {
+    pub fn changed(&self) -> usize {
+        self.layers.iter().map(|layer| layer.changed).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changed() == 0
+    }
+}
+
+impl Network {
+    /// Whether both networks have the same layers, regardless of weights.
+    pub fn same_topology(&self, other: &Self) -> bool {
+        self.topology() == other.topology()
+    }
+
+    /// Euclidean distance between both networks' weights.
+    pub fn l2_distance(&self, other: &Self) -> Result<f32, Error> {
+        self.check_topology(other)?;
+
+        Ok(self
+            .weights()
+            .zip(other.weights())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>()
+            .sqrt())
+    }
+
+    /// One minus the cosine similarity of both networks' weights, i.e. zero
+    /// for networks whose weights point the same way and two for opposite
+    /// ones.
+    ///
+    /// Networks with all weights zeroed have no direction and are reported
+    /// as one apart (unless both are, in which case they're zero apart).
+    pub fn cosine_distance(&self, other: &Self) -> Result<f32, Error> {
+        self.check_topology(other)?;
+
+        let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+
+        for (a, b) in self.weights().zip(other.weights()) {
+            dot += a * b;
+            norm_a += a * a;
+            norm_b += b * b;
+        }
+
+        Ok(match (norm_a > 0.0, norm_b > 0.0) {
+            (true, true) => (1.0 - dot / (norm_a.sqrt() * norm_b.sqrt())).clamp(0.0, 2.0),
+            (false, false) => 0.0,
+            _ => 1.0,
+        })
+    }
+
+    /// Compares both networks weight by weight; weights that differ by no
+    /// more than `tolerance` count as unchanged.
+    pub fn diff(&self, other: &Self, tolerance: f32) -> Result<NetworkDiff, Error> {
+        self.check_topology(other)?;
+
+        let layers = self
+            .layers
+            .iter()
+            .zip(&other.layers)
+            .map(|(a, b)| {
+                let topology = a.topology();
+                let input_size = a.input_size();
+                let units = topology.blocks(input_size) / topology.kind.gates();
+
+                let mut neurons: Vec<_> = (0..units)
+                    .map(|neuron| NeuronDiff {
+                        neuron,
+                        changed: 0,
+                        max_change: 0.0,
+                    })
+                    .collect();
+
+                let mut distance = 0.0;
+
+                for (idx, (a, b)) in a.weights().zip(b.weights()).enumerate() {
+                    let change = b - a;
+
+                    distance += change * change;
+
+                    if change.abs() <= tolerance {
+                        continue;
+                    }
+
+                    let neuron = &mut neurons[neuron_of(&topology, input_size, idx)];
+
+                    neuron.changed += 1;
+
+                    if change.abs() > neuron.max_change.abs() {
+                        neuron.max_change = change;
+                    }
+                }
+
+                neurons.retain(|neuron| neuron.changed > 0);
+
+                LayerDiff {
+                    changed: neurons.iter().map(|neuron| neuron.changed).sum(),
+                    distance: distance.sqrt(),
+                    neurons,
+                }
+            })
+            .collect();
+
+        Ok(NetworkDiff { layers })
+    }
+
+    fn check_topology(&self, other: &Self) -> Result<(), Error> {
+        if self.same_topology(other) {
+            Ok(())
+        } else {
+            Err(Error::TopologyMismatch)
+        }
+    }
+}
+
+/// Neuron (or, for convolutions, channel) the layer's `idx`-th weight
+/// belongs to.
+fn neuron_of(topology: &LayerTopology, input_size: usize, idx: usize) -> usize {
+    let block = 1 + topology.fan_in(input_size);
+    let blocks = topology.blocks(input_size);
+
+    if idx < blocks * block {
+        // Gates are laid out one after another
+        (idx / block) % (blocks / topology.kind.gates())
+    } else {
+        // Plastic layers' rules: a learning rate plus four coefficients per
+        // input
+        (idx - blocks * block) / (1 + 4 * input_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn network(kind: LayerKind, weights: Vec<f32>) -> Network {
+        Network::from_weights(
+            &[LayerTopology::new(1), LayerTopology::new(2).with_kind(kind)],
+            weights,
+        )
+    }
+
+    mod eq {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let a = network(LayerKind::Dense, vec![0.1, 0.2, 0.3, 0.4]);
+
+            assert_eq!(a, network(LayerKind::Dense, vec![0.1, 0.2, 0.3, 0.4]));
+            assert_ne!(a, network(LayerKind::Dense, vec![0.1, 0.2, 0.3, 0.5]));
+            assert_ne!(a, network(LayerKind::Recurrent, vec![0.1; 8]));
+        }
+    }
+
+    mod distance {
+        use super::*;
+
+        #[test]
+        fn l2() {
+            let a = network(LayerKind::Dense, vec![1.0, 0.0, 0.0, 2.0]);
+            let b = network(LayerKind::Dense, vec![4.0, 0.0, 0.0, -2.0]);
+
+            assert_relative_eq!(a.l2_distance(&b).unwrap(), 5.0);
+            assert_relative_eq!(a.l2_distance(&a).unwrap(), 0.0);
+        }
+
+        #[test]
+        fn cosine() {
+            let a = network(LayerKind::Dense, vec![1.0, 0.0, 0.0, 0.0]);
+            let b = network(LayerKind::Dense, vec![0.0, 3.0, 0.0, 0.0]);
+            let c = network(LayerKind::Dense, vec![-2.0, 0.0, 0.0, 0.0]);
+            let zero = network(LayerKind::Dense, vec![0.0; 4]);
+
+            assert_relative_eq!(a.cosine_distance(&a).unwrap(), 0.0);
+            assert_relative_eq!(a.cosine_distance(&b).unwrap(), 1.0);
+            assert_relative_eq!(a.cosine_distance(&c).unwrap(), 2.0);
+            assert_relative_eq!(a.cosine_distance(&zero).unwrap(), 1.0);
+            assert_relative_eq!(zero.cosine_distance(&zero).unwrap(), 0.0);
+        }
+
+        #[test]
+        fn rejects_different_topologies() {
+            let a = network(LayerKind::Dense, vec![0.0; 4]);
+            let b = network(LayerKind::Recurrent, vec![0.0; 8]);
+
+            assert_eq!(a.l2_distance(&b), Err(Error::TopologyMismatch));
+            assert_eq!(a.cosine_distance(&b), Err(Error::TopologyMismatch));
+            assert_eq!(a.diff(&b, 0.0), Err(Error::TopologyMismatch));
+        }
+    }
+
+    mod diff {
+        use super::*;
+
+        #[test]
+        fn test() {
+            // Gates of both neurons come one after another: [bias, x, h0, h1]
+            let a = network(LayerKind::Gru, vec![0.0; 24]);
+
+            let mut weights = vec![0.0; 24];
+            weights[1] = 0.5; // update gate, neuron #0
+            weights[5] = 0.001; // update gate, neuron #1 (below tolerance)
+            weights[8] = -1.0; // reset gate, neuron #0
+            weights[20] = 0.25; // candidate, neuron #1
+
+            let b = network(LayerKind::Gru, weights);
+            let diff = a.diff(&b, 0.01).unwrap();
+
+            assert_eq!(diff.changed(), 3);
+            assert_eq!(
+                diff.layers[0].neurons,
+                vec![
+                    NeuronDiff {
+                        neuron: 0,
+                        changed: 2,
+                        max_change: -1.0,
+                    },
+                    NeuronDiff {
+                        neuron: 1,
+                        changed: 1,
+                        max_change: 0.25,
+                    },
+                ]
+            );
+
+            assert_relative_eq!(
+                diff.layers[0].distance,
+                a.l2_distance(&b).unwrap(),
+                epsilon = 1e-6
+            );
+
+            assert!(a.diff(&a, 0.0).unwrap().is_empty());
+        }
+
+        #[test]
+        fn plastic() {
+            let a = network(LayerKind::Plastic, vec![0.0; 14]);
+
+            // [bias, w] per neuron, then [eta, a, b, c, d] per neuron
+            let mut weights = vec![0.0; 14];
+            weights[13] = 1.0;
+
+            let b = network(LayerKind::Plastic, weights);
+
+            assert_eq!(
+                a.diff(&b, 0.0).unwrap().layers[0].neurons,
+                vec![NeuronDiff {
+                    neuron: 1,
+                    changed: 1,
+                    max_change: 1.0,
+                }]
+            );
+        }
+    }
+}
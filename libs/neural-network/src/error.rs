@@ -0,0 +1,97 @@
+use std::{error, fmt};
+
+use crate::layer_topology::LayerKind;
+
+/// Reason an operation on a network failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Networks need at least two layers - inputs and outputs.
+    NotEnoughLayers(usize),
+    EmptyLayer {
+        layer: usize,
+    },
+    /// Layer's input size doesn't match the previous layer's output size.
+    LayerSizeMismatch {
+        layer: usize,
+        expected: usize,
+        actual: usize,
+    },
+    WeightCountMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    InputSizeMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    /// The operation only works on hidden layers.
+    NotAHiddenLayer(usize),
+    NeuronOutOfRange {
+        layer: usize,
+        neuron: usize,
+    },
+    /// The operation doesn't support layers of this kind.
+    UnsupportedLayer {
+        layer: usize,
+        kind: LayerKind,
+    },
+    /// Layer's size or kind-specific settings don't fit its inputs.
+    InvalidLayer {
+        layer: usize,
+        reason: &'static str,
+    },
+    /// The operation requires networks of the same topology.
+    TopologyMismatch,
+    /// Layer's number of weights (or the network's total) doesn't fit in
+    /// `usize`.
+    TooManyWeights {
+        layer: usize,
+    },
+    /// CTRNN's topology (see `CtrnnTopology`) is invalid.
+    InvalidCtrnn {
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughLayers(count) => write!(
+                f,
+                "expected at least two layers (inputs and outputs), got {count}"
+            ),
+            Self::EmptyLayer { layer } => write!(f, "layer #{layer} has no neurons"),
+            Self::LayerSizeMismatch {
+                layer,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "layer #{layer} expects {actual} inputs, but the previous layer has {expected} outputs"
+            ),
+            Self::WeightCountMismatch { expected, actual } => write!(
+                f,
+                "got too {} weights: topology requires {expected}, got {actual}",
+                if actual < expected { "few" } else { "many" }
+            ),
+            Self::InputSizeMismatch { expected, actual } => {
+                write!(f, "expected {expected} inputs, got {actual}")
+            }
+            Self::NotAHiddenLayer(layer) => write!(f, "layer #{layer} is not a hidden layer"),
+            Self::NeuronOutOfRange { layer, neuron } => {
+                write!(f, "layer #{layer} has no neuron #{neuron}")
+            }
+            Self::UnsupportedLayer { layer, kind } => {
+                write!(f, "layer #{layer} ({kind:?}) is not supported here")
+            }
+            Self::InvalidLayer { layer, reason } => write!(f, "layer #{layer} is invalid: {reason}"),
+            Self::TopologyMismatch => write!(f, "networks have different topologies"),
+            Self::TooManyWeights { layer } => {
+                write!(f, "layer #{layer} has too many weights to be addressed")
+            }
+            Self::InvalidCtrnn { reason } => write!(f, "CTRNN is invalid: {reason}"),
+        }
+    }
+}
+
+impl error::Error for Error {}
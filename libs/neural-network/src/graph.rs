@@ -0,0 +1,402 @@
+//! Network's structure as a graph of neurons, exportable to GraphViz DOT or
+//! JSON.
+
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::*;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Graph {
+    /// Input layer included, i.e. numbered as in the topology.
+    pub layers: Vec<GraphLayer>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GraphLayer {
+    /// `None` for the input layer.
+    pub kind: Option<LayerKind>,
+    /// `None` for the input layer.
+    pub activation: Option<Activation>,
+    pub neurons: Vec<GraphNode>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GraphNode {
+    /// One per gate (a single one for non-gated layers); empty for inputs.
+    pub biases: Vec<f32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct NodeId {
+    pub layer: usize,
+    pub neuron: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GraphEdge {
+    pub from: NodeId,
+    pub to: NodeId,
+    pub weight: f32,
+    /// Gate the weight belongs to; `None` for non-gated layers.
+    pub gate: Option<&'static str>,
+}
+
+impl GraphEdge {
+    /// Whether the edge connects neurons of the same layer, carrying values
+    /// from the previous `propagate` call.
+    pub fn is_recurrent(&self) -> bool {
+        self.from.layer == self.to.layer
+    }
+}
+
+impl Network {
+    pub fn graph(&self) -> Graph {
+        let topology = self.topology();
+
+        let mut layers = vec![GraphLayer {
+            kind: None,
+            activation: None,
+            neurons: vec![GraphNode { biases: Vec::new() }; topology[0].neurons],
+        }];
+
+        let mut edges = Vec::new();
+
+        for (idx, (layer, inputs)) in self.layers.iter().zip(&topology).enumerate() {
+            let to_layer = idx + 1;
+            let kind = layer.kind();
+            let gate_names = kind.gate_names();
+            let mut neurons = vec![GraphNode { biases: Vec::new() }; layer.output_size()];
+            match kind {
+                LayerKind::Softmax | LayerKind::LayerNorm => {
+                    // One-to-one, weighted by layer normalization's gains
+                    let mut params = layer.weights();
+
+                    for (neuron, node) in neurons.iter_mut().enumerate() {
+                        let weight = match params.next() {
+                            Some(bias) => {
+                                node.biases.push(bias);
+                                params.next().unwrap()
+                            }
+                            None => 1.0,
+                        };
+
+                        edges.push(GraphEdge {
+                            from: NodeId { layer: idx, neuron },
+                            to: NodeId {
+                                layer: to_layer,
+                                neuron,
+                            },
+                            weight,
+                            gate: None,
+                        });
+                    }
+                }
+
+                LayerKind::Conv1d { kernel } => {
+                    // Shared weights get repeated for every position
+                    let params: Vec<_> = layer.weights().collect();
+                    let positions = inputs.neurons;
+
+                    for (neuron, node) in neurons.iter_mut().enumerate() {
+                        let (channel, position) = (neuron / positions, neuron % positions);
+                        let params = &params[channel * (1 + kernel)..][..1 + kernel];
+
+                        node.biases.push(params[0]);
+
+                        for (offset, &weight) in params[1..].iter().enumerate() {
+                            let Some(from) = (position + offset)
+                                .checked_sub(kernel / 2)
+                                .filter(|&from| from < positions)
+                            else {
+                                continue;
+                            };
+
+                            edges.push(GraphEdge {
+                                from: NodeId {
+                                    layer: idx,
+                                    neuron: from,
+                                },
+                                to: NodeId {
+                                    layer: to_layer,
+                                    neuron,
+                                },
+                                weight,
+                                gate: None,
+                            });
+                        }
+                    }
+                }
+
+                _ => {
+                    let mut weights = layer.weights();
+
+                    for gate in 0..kind.gates() {
+                        let gate = gate_names.get(gate).copied();
+
+                        for (neuron, node) in neurons.iter_mut().enumerate() {
+                            let to = NodeId {
+                                layer: to_layer,
+                                neuron,
+                            };
+
+                            node.biases.push(weights.next().unwrap());
+
+                            let recurrent =
+                                layer.topology().fan_in(inputs.neurons) - inputs.neurons;
+
+                            let sources = (0..inputs.neurons)
+                                .map(|neuron| (idx, neuron))
+                                .chain((0..recurrent).map(|neuron| (to_layer, neuron)));
+
+                            for (layer, neuron) in sources {
+                                edges.push(GraphEdge {
+                                    from: NodeId { layer, neuron },
+                                    to,
+                                    weight: weights.next().unwrap(),
+                                    gate,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+
+            layers.push(GraphLayer {
+                kind: Some(kind),
+                activation: Some(layer.activation()),
+                neurons,
+            });
+        }
+
+        Graph { layers, edges }
+    }
+}
+
+impl Graph {
+    /// Drops edges whose weights' magnitude is below `min_weight`.
+    pub fn pruned(mut self, min_weight: f32) -> Self {
+        self.edges.retain(|edge| edge.weight.abs() >= min_weight);
+        self
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Renders the graph in GraphViz's DOT language, one cluster per layer.
+    ///
+    /// Positive weights are blue, negative ones are red; the stronger the
+    /// weight, the thicker and more opaque its edge. Recurrent edges are
+    /// dashed.
+    pub fn to_dot(&self) -> String {
+        let max_weight = self
+            .edges
+            .iter()
+            .map(|edge| edge.weight.abs())
+            .fold(0.0, f32::max);
+
+        let mut dot = String::new();
+
+        writeln!(dot, "digraph network {{").unwrap();
+        writeln!(dot, "    rankdir=LR;").unwrap();
+        writeln!(dot, "    node [shape=circle];").unwrap();
+
+        for (idx, layer) in self.layers.iter().enumerate() {
+            let label = match (layer.kind, layer.activation) {
+                (Some(kind), Some(activation)) => format!("#{idx}: {kind:?}, {activation:?}"),
+                _ => "inputs".into(),
+            };
+
+            writeln!(dot).unwrap();
+            writeln!(dot, "    subgraph cluster_{idx} {{").unwrap();
+            writeln!(dot, "        label=\"{label}\";").unwrap();
+
+            for (neuron, node) in layer.neurons.iter().enumerate() {
+                let label = match node.biases.as_slice() {
+                    [bias] => format!("{neuron}\\nb={bias:.3}"),
+                    _ => neuron.to_string(),
+                };
+
+                writeln!(dot, "        n{idx}_{neuron} [label=\"{label}\"];").unwrap();
+            }
+
+            writeln!(dot, "    }}").unwrap();
+        }
+
+        if !self.edges.is_empty() {
+            writeln!(dot).unwrap();
+        }
+
+        for edge in &self.edges {
+            let strength = if max_weight > 0.0 {
+                edge.weight.abs() / max_weight
+            } else {
+                0.0
+            };
+
+            let color = if edge.weight >= 0.0 {
+                "#1f77b4"
+            } else {
+                "#d62728"
+            };
+
+            let alpha = (64.0 + 191.0 * strength).round() as u8;
+
+            let label = match edge.gate {
+                Some(gate) => format!("{gate}: {:.3}", edge.weight),
+                None => format!("{:.3}", edge.weight),
+            };
+
+            write!(
+                dot,
+                "    n{}_{} -> n{}_{} [label=\"{label}\", color=\"{color}{alpha:02x}\", penwidth={:.2}",
+                edge.from.layer,
+                edge.from.neuron,
+                edge.to.layer,
+                edge.to.neuron,
+                0.5 + 2.5 * strength,
+            )
+            .unwrap();
+
+            if edge.is_recurrent() {
+                write!(dot, ", style=dashed, constraint=false").unwrap();
+            }
+
+            writeln!(dot, "];").unwrap();
+        }
+
+        writeln!(dot, "}}").unwrap();
+
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network() -> Network {
+        Network::new(vec![
+            Layer::Dense(DenseLayer::new(
+                vec![0.1, 0.2],
+                vec![0.5, -0.25, 0.01, 1.0],
+                Activation::ReLU,
+            )),
+            Layer::Recurrent(RecurrentLayer::new(
+                vec![-0.3],
+                vec![2.0, -2.0],
+                vec![0.5],
+                Activation::Tanh,
+            )),
+        ])
+    }
+
+    mod graph {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let graph = network().graph();
+
+            assert_eq!(graph.layers.len(), 3);
+            assert_eq!(graph.layers[0].neurons.len(), 2);
+            assert_eq!(graph.layers[1].neurons[1].biases, vec![0.2]);
+            assert_eq!(graph.layers[2].kind, Some(LayerKind::Recurrent));
+            assert_eq!(graph.layers[2].activation, Some(Activation::Tanh));
+
+            let weights: Vec<_> = graph.edges.iter().map(|edge| edge.weight).collect();
+
+            assert_eq!(weights, vec![0.5, -0.25, 0.01, 1.0, 2.0, -2.0, 0.5]);
+
+            assert_eq!(
+                graph.edges[1].from,
+                NodeId {
+                    layer: 0,
+                    neuron: 1
+                }
+            );
+
+            assert_eq!(
+                graph.edges[2].to,
+                NodeId {
+                    layer: 1,
+                    neuron: 1
+                }
+            );
+
+            assert!(graph.edges[6].is_recurrent());
+            assert!(!graph.edges[5].is_recurrent());
+        }
+
+        #[test]
+        fn gated() {
+            let layers = &[
+                LayerTopology::new(2),
+                LayerTopology::new(1).with_kind(LayerKind::Gru),
+            ];
+
+            let graph = Network::from_weights(layers, vec![0.5; 12]).graph();
+
+            assert_eq!(graph.layers[1].neurons[0].biases.len(), 3);
+            assert_eq!(graph.edges.len(), 9);
+            assert_eq!(graph.edges[0].gate, Some("update"));
+            assert_eq!(graph.edges[8].gate, Some("candidate"));
+        }
+
+        #[test]
+        fn pruned() {
+            let graph = network().graph().pruned(0.3);
+
+            let weights: Vec<_> = graph.edges.iter().map(|edge| edge.weight).collect();
+
+            assert_eq!(weights, vec![0.5, 1.0, 2.0, -2.0, 0.5]);
+        }
+    }
+
+    mod to_dot {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let dot = network().graph().to_dot();
+
+            assert!(dot.starts_with("digraph network {\n"));
+            assert!(dot.contains("        label=\"#2: Recurrent, Tanh\";\n"));
+            assert!(dot.contains("        n1_0 [label=\"0\\nb=0.100\"];\n"));
+
+            assert!(dot.contains(
+                "    n0_0 -> n1_0 [label=\"0.500\", color=\"#1f77b470\", penwidth=1.12];\n"
+            ));
+
+            assert!(dot.contains(
+                "    n1_1 -> n2_0 [label=\"-2.000\", color=\"#d62728ff\", penwidth=3.00];\n"
+            ));
+
+            assert!(dot.contains(
+                "    n2_0 -> n2_0 [label=\"0.500\", color=\"#1f77b470\", penwidth=1.12, style=dashed, constraint=false];\n"
+            ));
+
+            assert!(dot.ends_with("}\n"));
+        }
+    }
+
+    mod to_json {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let json: serde_json::Value =
+                serde_json::from_str(&network().graph().to_json()).unwrap();
+
+            assert_eq!(json["layers"][0]["kind"], serde_json::Value::Null);
+            assert_eq!(json["layers"][1]["kind"], "Dense");
+            assert_eq!(json["layers"][2]["activation"], "Tanh");
+            assert_eq!(json["edges"].as_array().unwrap().len(), 7);
+            assert_eq!(json["edges"][6]["from"]["layer"], 2);
+            assert_eq!(json["edges"][6]["weight"], 0.5);
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use std::f32::consts::PI;
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Strategy of drawing a layer's initial weights.
+///
+/// `fan_in` is the number of weights per neuron (inputs plus, for recurrent
+/// layers, the layer's own outputs) and `fan_out` is the number of neurons.
+///
+/// Except for `Uniform`, biases start at zero.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Initializer {
+    /// Uniform within `[-limit, limit]`, biases included.
+    Uniform(f32),
+    /// Glorot & Bengio (2010), uniform within `±sqrt(6 / (fan_in + fan_out))`.
+    XavierUniform,
+    /// Glorot & Bengio (2010), normal with std of `sqrt(2 / (fan_in + fan_out))`.
+    XavierNormal,
+    /// He et al. (2015), uniform within `±sqrt(6 / fan_in)`; suited for ReLU.
+    HeUniform,
+    /// He et al. (2015), normal with std of `sqrt(2 / fan_in)`; suited for
+    /// ReLU.
+    HeNormal,
+    /// LeCun et al. (1998), uniform within `±sqrt(3 / fan_in)`.
+    LeCunUniform,
+    /// LeCun et al. (1998), normal with std of `sqrt(1 / fan_in)`.
+    LeCunNormal,
+    Zeros,
+    Constant(f32),
+    /// Saxe et al. (2013) - weights of each neuron (or of each gate's neuron)
+    /// form a (semi-)orthogonal matrix.
+    Orthogonal,
+}
+
+impl Default for Initializer {
+    fn default() -> Self {
+        Self::Uniform(1.0)
+    }
+}
+
+impl Initializer {
+    /// Draws parameters for `gates` blocks of `fan_out` neurons each, neuron
+    /// by neuron: bias and then `fan_in` weights - i.e. in the order layers
+    /// expect them in `from_weights`.
+    pub(crate) fn weights(
+        self,
+        rng: &mut dyn RngCore,
+        gates: usize,
+        fan_out: usize,
+        fan_in: usize,
+    ) -> Vec<f32> {
+        let mut weights = Vec::with_capacity(gates * fan_out * (1 + fan_in));
+
+        for _ in 0..gates {
+            if self == Self::Orthogonal {
+                let matrix = Self::orthogonal(rng, fan_out, fan_in);
+
+                for row in matrix.chunks_exact(fan_in) {
+                    weights.push(0.0);
+                    weights.extend_from_slice(row);
+                }
+
+                continue;
+            }
+
+            for _ in 0..fan_out {
+                weights.push(match self {
+                    Self::Uniform(limit) => rng.gen_range(-limit..=limit),
+                    _ => 0.0,
+                });
+
+                for _ in 0..fan_in {
+                    weights.push(self.draw(rng, fan_in as f32, fan_out as f32));
+                }
+            }
+        }
+
+        weights
+    }
+
+    fn draw(self, rng: &mut dyn RngCore, fan_in: f32, fan_out: f32) -> f32 {
+        let uniform = |rng: &mut dyn RngCore, limit: f32| rng.gen_range(-limit..=limit);
+
+        match self {
+            Self::Uniform(limit) => uniform(rng, limit),
+            Self::XavierUniform => uniform(rng, (6.0 / (fan_in + fan_out)).sqrt()),
+            Self::XavierNormal => (2.0 / (fan_in + fan_out)).sqrt() * normal(rng),
+            Self::HeUniform => uniform(rng, (6.0 / fan_in).sqrt()),
+            Self::HeNormal => (2.0 / fan_in).sqrt() * normal(rng),
+            Self::LeCunUniform => uniform(rng, (3.0 / fan_in).sqrt()),
+            Self::LeCunNormal => (1.0 / fan_in).sqrt() * normal(rng),
+            Self::Zeros => 0.0,
+            Self::Constant(value) => value,
+            Self::Orthogonal => unreachable!(),
+        }
+    }
+
+    /// Returns a row-major `rows x cols` matrix with orthonormal rows (when
+    /// `rows <= cols`) or columns (otherwise).
+    fn orthogonal(rng: &mut dyn RngCore, rows: usize, cols: usize) -> Vec<f32> {
+        let (short, long) = if rows <= cols {
+            (rows, cols)
+        } else {
+            (cols, rows)
+        };
+
+        let mut vectors: Vec<f32> = (0..short * long).map(|_| normal(rng)).collect();
+
+        // Gram-Schmidt
+        for i in 0..short {
+            for j in 0..i {
+                let (done, rest) = vectors.split_at_mut(i * long);
+                let prev = &done[j * long..][..long];
+                let curr = &mut rest[..long];
+
+                let dot: f32 = prev.iter().zip(curr.iter()).map(|(a, b)| a * b).sum();
+
+                for (curr, prev) in curr.iter_mut().zip(prev) {
+                    *curr -= dot * prev;
+                }
+            }
+
+            let curr = &mut vectors[i * long..][..long];
+            let norm = curr
+                .iter()
+                .map(|x| x * x)
+                .sum::<f32>()
+                .sqrt()
+                .max(f32::EPSILON);
+
+            for x in curr {
+                *x /= norm;
+            }
+        }
+
+        if rows <= cols {
+            return vectors;
+        }
+
+        let mut matrix = vec![0.0; rows * cols];
+
+        for col in 0..cols {
+            for row in 0..rows {
+                matrix[row * cols + col] = vectors[col * rows + row];
+            }
+        }
+
+        matrix
+    }
+}
+
+/// Draws from the standard normal distribution (Box-Muller transform).
+fn normal(rng: &mut dyn RngCore) -> f32 {
+    let u1 = 1.0 - rng.gen::<f32>();
+    let u2 = rng.gen::<f32>();
+
+    (-2.0 * libm::logf(u1)).sqrt() * libm::cosf(2.0 * PI * u2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn weights(initializer: Initializer, gates: usize, fan_out: usize, fan_in: usize) -> Vec<f32> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        initializer.weights(&mut rng, gates, fan_out, fan_in)
+    }
+
+    /// Weights without the biases.
+    fn matrix(weights: &[f32], fan_in: usize) -> Vec<f32> {
+        weights
+            .chunks_exact(1 + fan_in)
+            .flat_map(|neuron| &neuron[1..])
+            .cloned()
+            .collect()
+    }
+
+    fn std(values: &[f32]) -> f32 {
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+
+        (values.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / values.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn uniform() {
+        let weights = weights(Initializer::default(), 1, 2, 3);
+
+        assert_relative_eq!(weights[0], -0.6255188);
+        assert_relative_eq!(weights[1], 0.67383957);
+        assert!(weights.iter().all(|w| (-1.0..=1.0).contains(w)));
+    }
+
+    #[test]
+    fn xavier_and_he() {
+        let xavier = weights(Initializer::XavierUniform, 1, 100, 50);
+        let limit = (6.0f32 / 150.0).sqrt();
+
+        assert!(matrix(&xavier, 50).iter().all(|w| w.abs() <= limit));
+        assert!(xavier.chunks_exact(51).all(|neuron| neuron[0] == 0.0));
+
+        let he = matrix(&weights(Initializer::HeNormal, 1, 100, 50), 50);
+
+        assert_relative_eq!(std(&he), (2.0f32 / 50.0).sqrt(), epsilon = 0.01);
+    }
+
+    #[test]
+    fn constant() {
+        let weights = weights(Initializer::Constant(0.5), 2, 1, 2);
+
+        assert_eq!(weights, vec![0.0, 0.5, 0.5, 0.0, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn orthogonal() {
+        for (rows, cols) in [(3, 5), (5, 3), (4, 4)] {
+            let weights = weights(Initializer::Orthogonal, 2, rows, cols);
+            let gate = matrix(&weights[..rows * (1 + cols)], cols);
+
+            let (outer, inner) = if rows <= cols {
+                (rows, cols)
+            } else {
+                (cols, rows)
+            };
+
+            for a in 0..outer {
+                for b in 0..outer {
+                    let dot: f32 = (0..inner)
+                        .map(|k| {
+                            if rows <= cols {
+                                gate[a * cols + k] * gate[b * cols + k]
+                            } else {
+                                gate[k * cols + a] * gate[k * cols + b]
+                            }
+                        })
+                        .sum();
+
+                    let expected = if a == b { 1.0 } else { 0.0 };
+
+                    assert_relative_eq!(dot, expected, epsilon = 1e-5);
+                }
+            }
+        }
+    }
+}
@@ -1,102 +1,316 @@
-use rand::RngCore;
+pub use self::{
+    conv1d::*, dense::*, gru::*, layer_norm::*, lstm::*, plastic::*, recurrent::*, softmax::*,
+};
 
-use crate::neuron::Neuron;
+use crate::*;
 
-#[derive(Clone)]
-pub struct Layer {
-    pub neurons: Vec<Neuron>,
+mod conv1d;
+mod dense;
+mod gate;
+mod gru;
+mod layer_norm;
+mod lstm;
+mod plastic;
+mod recurrent;
+mod softmax;
+
+#[derive(Clone, Debug)]
+pub enum Layer {
+    Dense(DenseLayer),
+    Recurrent(RecurrentLayer),
+    Lstm(LstmLayer),
+    Gru(GruLayer),
+    Plastic(PlasticLayer),
+    Softmax(SoftmaxLayer),
+    Norm(LayerNormLayer),
+    Conv1d(Conv1dLayer),
 }
 
 impl Layer {
-    pub fn new(neurons: Vec<Neuron>) -> Self {
-        Self { neurons }
-    }
+    /// Draws the weights according to the topology's initializer; plastic
+    /// layers' learning rates are drawn from `[0, 0.1]` and their rules'
+    /// coefficients from `[-1, 1]`, while layer normalization always starts
+    /// as the identity (zero biases, unit gains).
+    pub fn random(rng: &mut dyn RngCore, input_size: usize, topology: &LayerTopology) -> Self {
+        let neurons = topology.neurons;
+        let fan_in = topology.fan_in(input_size);
 
-    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        self.neurons
-            .iter()
-            .map(|neuron| neuron.propagate(&inputs))
-            .collect()
-    }
+        let mut weights = match topology.kind {
+            LayerKind::Softmax => Vec::new(),
+            LayerKind::LayerNorm => [0.0, 1.0].repeat(neurons),
+            LayerKind::Conv1d { .. } => {
+                let channels = topology.blocks(input_size);
+
+                topology.initializer.weights(rng, 1, channels, fan_in)
+            }
+            kind => topology
+                .initializer
+                .weights(rng, kind.gates(), neurons, fan_in),
+        };
 
-    pub fn random(rng: &mut dyn RngCore, input_size: usize, output_size: usize) -> Self {
-        let neurons = (0..output_size)
-            .map(|_| Neuron::random(rng, input_size))
-            .collect();
+        if topology.kind == LayerKind::Plastic {
+            for _ in 0..neurons {
+                weights.push(rng.gen_range(0.0..=0.1));
 
-        Layer { neurons }
+                for _ in 0..4 * input_size {
+                    weights.push(rng.gen_range(-1.0..=1.0));
+                }
+            }
+        }
+
+        Self::from_weights(input_size, topology, &mut weights.into_iter())
     }
 
     pub fn from_weights(
         input_size: usize,
-        output_size: usize,
+        topology: &LayerTopology,
         weights: &mut dyn Iterator<Item = f32>,
     ) -> Self {
-        let neurons = (0..output_size)
-            .map(|_| Neuron::from_weights(input_size, weights))
-            .collect();
+        let LayerTopology {
+            neurons,
+            activation,
+            kind,
+            ..
+        } = *topology;
 
-        Self { neurons }
+        match kind {
+            LayerKind::Dense => Self::Dense(DenseLayer::from_weights(
+                input_size, neurons, activation, weights,
+            )),
+            LayerKind::Recurrent => Self::Recurrent(RecurrentLayer::from_weights(
+                input_size, neurons, activation, weights,
+            )),
+            LayerKind::Lstm => Self::Lstm(LstmLayer::from_weights(
+                input_size, neurons, activation, weights,
+            )),
+            LayerKind::Gru => Self::Gru(GruLayer::from_weights(
+                input_size, neurons, activation, weights,
+            )),
+            LayerKind::Plastic => Self::Plastic(PlasticLayer::from_weights(
+                input_size, neurons, activation, weights,
+            )),
+            LayerKind::Softmax => Self::Softmax(SoftmaxLayer::new(neurons)),
+            LayerKind::LayerNorm => {
+                Self::Norm(LayerNormLayer::from_weights(neurons, activation, weights))
+            }
+            LayerKind::Conv1d { kernel } => Self::Conv1d(Conv1dLayer::from_weights(
+                input_size,
+                neurons / input_size,
+                kernel,
+                activation,
+                weights,
+            )),
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    pub fn input_size(&self) -> usize {
+        match self {
+            Self::Dense(layer) => layer.input_size(),
+            Self::Recurrent(layer) => layer.input_size(),
+            Self::Lstm(layer) => layer.input_size(),
+            Self::Gru(layer) => layer.input_size(),
+            Self::Plastic(layer) => layer.input_size(),
+            Self::Softmax(layer) => layer.input_size(),
+            Self::Norm(layer) => layer.input_size(),
+            Self::Conv1d(layer) => layer.input_size(),
+        }
+    }
 
-    mod random {
-        use super::*;
-        use approx::assert_relative_eq;
-        use rand::SeedableRng;
-        use rand_chacha::ChaCha8Rng;
+    pub fn output_size(&self) -> usize {
+        match self {
+            Self::Dense(layer) => layer.output_size(),
+            Self::Recurrent(layer) => layer.output_size(),
+            Self::Lstm(layer) => layer.output_size(),
+            Self::Gru(layer) => layer.output_size(),
+            Self::Plastic(layer) => layer.output_size(),
+            Self::Softmax(layer) => layer.output_size(),
+            Self::Norm(layer) => layer.output_size(),
+            Self::Conv1d(layer) => layer.output_size(),
+        }
+    }
 
-        #[test]
-        fn test() {
-            let mut rng = ChaCha8Rng::from_seed(Default::default());
+    pub fn activation(&self) -> Activation {
+        match self {
+            Self::Dense(layer) => layer.activation(),
+            Self::Recurrent(layer) => layer.activation(),
+            Self::Lstm(layer) => layer.activation(),
+            Self::Gru(layer) => layer.activation(),
+            Self::Plastic(layer) => layer.activation(),
+            Self::Softmax(layer) => layer.activation(),
+            Self::Norm(layer) => layer.activation(),
+            Self::Conv1d(layer) => layer.activation(),
+        }
+    }
 
-            let layer = Layer::random(&mut rng, 3, 2);
+    pub fn kind(&self) -> LayerKind {
+        match self {
+            Self::Dense(_) => LayerKind::Dense,
+            Self::Recurrent(_) => LayerKind::Recurrent,
+            Self::Lstm(_) => LayerKind::Lstm,
+            Self::Gru(_) => LayerKind::Gru,
+            Self::Plastic(_) => LayerKind::Plastic,
+            Self::Softmax(_) => LayerKind::Softmax,
+            Self::Norm(_) => LayerKind::LayerNorm,
+            Self::Conv1d(layer) => LayerKind::Conv1d {
+                kernel: layer.kernel(),
+            },
+        }
+    }
 
-            let actual_biases: Vec<f32> = layer.neurons.iter().map(|neuron| neuron.bias).collect();
+    /// Topology this layer could've been created from (with the default
+    /// initializer).
+    pub fn topology(&self) -> LayerTopology {
+        LayerTopology::new(self.output_size())
+            .with_activation(self.activation())
+            .with_kind(self.kind())
+    }
 
-            let actual_weights: Vec<&[f32]> = layer
-                .neurons
-                .iter()
-                .map(|neuron| neuron.weights.as_slice())
-                .collect();
+    pub fn propagate(&mut self, inputs: &[f32]) -> Vec<f32> {
+        let mut outputs = vec![0.0; self.output_size()];
+        self.propagate_into(inputs, &mut outputs);
+        outputs
+    }
 
-            let expected_weights: Vec<&[f32]> = vec![
-                &[0.67383957, 0.8181262, 0.26284897],
-                &[-0.53516835, 0.069369674, -0.7648182],
-            ];
+    pub fn propagate_into(&mut self, inputs: &[f32], outputs: &mut [f32]) {
+        match self {
+            Self::Dense(layer) => layer.propagate_into(inputs, outputs),
+            Self::Recurrent(layer) => layer.propagate_into(inputs, outputs),
+            Self::Lstm(layer) => layer.propagate_into(inputs, outputs),
+            Self::Gru(layer) => layer.propagate_into(inputs, outputs),
+            Self::Plastic(layer) => layer.propagate_into(inputs, outputs),
+            Self::Softmax(layer) => layer.propagate_into(inputs, outputs),
+            Self::Norm(layer) => layer.propagate_into(inputs, outputs),
+            Self::Conv1d(layer) => layer.propagate_into(inputs, outputs),
+        }
+    }
 
-            assert_relative_eq!(actual_biases.as_slice(), [-0.6255188, 0.5238807].as_slice());
-            assert_relative_eq!(actual_weights.as_slice(), expected_weights.as_slice());
+    /// Like `propagate_into`, but also stores the neurons' values before the
+    /// (final) activation into `sums` - for gated layers that's the cell
+    /// state (LSTM) or the candidate (GRU).
+    pub fn propagate_traced(&mut self, inputs: &[f32], sums: &mut [f32], outputs: &mut [f32]) {
+        match self {
+            Self::Dense(layer) => layer.forward_train(inputs, sums, outputs),
+            Self::Recurrent(layer) => layer.propagate_traced(inputs, sums, outputs),
+            Self::Lstm(layer) => layer.propagate_traced(inputs, sums, outputs),
+            Self::Gru(layer) => layer.propagate_traced(inputs, sums, outputs),
+            Self::Plastic(layer) => layer.propagate_traced(inputs, sums, outputs),
+            Self::Softmax(layer) => layer.propagate_traced(inputs, sums, outputs),
+            Self::Norm(layer) => layer.propagate_traced(inputs, sums, outputs),
+            Self::Conv1d(layer) => layer.propagate_traced(inputs, sums, outputs),
         }
     }
 
-    mod propagate {
-        use approx::assert_relative_eq;
+    /// Propagates many inputs at once; stateful layers propagate each of
+    /// them from their current state, which is left unchanged.
+    pub fn propagate_batch(&self, inputs: &[f32], outputs: &mut [f32]) {
+        match self {
+            Self::Dense(layer) => layer.propagate_batch(inputs, outputs),
+            Self::Recurrent(layer) => layer.propagate_batch(inputs, outputs),
+            Self::Lstm(layer) => layer.propagate_batch(inputs, outputs),
+            Self::Gru(layer) => layer.propagate_batch(inputs, outputs),
+            Self::Plastic(layer) => layer.propagate_batch(inputs, outputs),
+            Self::Softmax(layer) => layer.propagate_batch(inputs, outputs),
+            Self::Norm(layer) => layer.propagate_batch(inputs, outputs),
+            Self::Conv1d(layer) => layer.propagate_batch(inputs, outputs),
+        }
+    }
 
-        use super::*;
+    pub fn weights(&self) -> Box<dyn Iterator<Item = f32> + '_> {
+        match self {
+            Self::Dense(layer) => Box::new(layer.weights()),
+            Self::Recurrent(layer) => Box::new(layer.weights()),
+            Self::Lstm(layer) => Box::new(layer.weights()),
+            Self::Gru(layer) => Box::new(layer.weights()),
+            Self::Plastic(layer) => Box::new(layer.weights()),
+            Self::Softmax(_) => Box::new(std::iter::empty()),
+            Self::Norm(layer) => Box::new(layer.weights()),
+            Self::Conv1d(layer) => Box::new(layer.weights()),
+        }
+    }
 
-        #[test]
-        fn test() {
-            let neurons = vec![
-                Neuron::new(0.0, vec![0.1, 0.2, 0.3]),
-                Neuron::new(0.0, vec![0.4, 0.5, 0.6]),
-            ];
+    pub(crate) fn weight_count(&self) -> usize {
+        match self {
+            Self::Dense(layer) => layer.weight_count(),
+            Self::Recurrent(layer) => layer.weight_count(),
+            Self::Lstm(layer) => layer.weight_count(),
+            Self::Gru(layer) => layer.weight_count(),
+            Self::Plastic(layer) => layer.weight_count(),
+            Self::Softmax(_) => 0,
+            Self::Norm(layer) => layer.weight_count(),
+            Self::Conv1d(layer) => layer.weight_count(),
+        }
+    }
 
-            let layer = Layer::new(vec![neurons[0].clone(), neurons[1].clone()]);
+    pub(crate) fn weights_mut(&mut self) -> Box<dyn Iterator<Item = &mut f32> + '_> {
+        match self {
+            Self::Dense(layer) => Box::new(layer.weights_mut()),
+            Self::Recurrent(layer) => Box::new(layer.weights_mut()),
+            Self::Lstm(layer) => Box::new(layer.weights_mut()),
+            Self::Gru(layer) => Box::new(layer.weights_mut()),
+            Self::Plastic(layer) => Box::new(layer.weights_mut()),
+            Self::Softmax(_) => Box::new(std::iter::empty()),
+            Self::Norm(layer) => Box::new(layer.weights_mut()),
+            Self::Conv1d(layer) => Box::new(layer.weights_mut()),
+        }
+    }
 
-            let inputs = &[0.5, 0.0, 0.5];
+    /// Propagates a single input (without updating the state) and stores the
+    /// neurons' values before the activation into `sums`.
+    ///
+    /// # Panics
+    ///
+    /// For layers that don't support backpropagation - see
+    /// `LayerKind::supports_backpropagation`.
+    pub(crate) fn forward_train(&self, inputs: &[f32], sums: &mut [f32], outputs: &mut [f32]) {
+        match self {
+            Self::Dense(layer) => layer.forward_train(inputs, sums, outputs),
+            Self::Recurrent(layer) => layer.forward_train(inputs, sums, outputs),
+            Self::Softmax(layer) => layer.propagate_traced(inputs, sums, outputs),
+            Self::Norm(layer) => layer.propagate_traced(inputs, sums, outputs),
+            _ => panic!(
+                "backpropagation through {:?} layers isn't supported",
+                self.kind()
+            ),
+        }
+    }
 
-            let actual_propagate = layer.propagate(inputs.to_vec());
-            let expected_propagate: Vec<f32> = neurons
-                .iter()
-                .map(|neuron| neuron.propagate(inputs))
-                .collect();
+    /// See `DenseLayer::backward`.
+    ///
+    /// # Panics
+    ///
+    /// For layers that don't support backpropagation - see
+    /// `LayerKind::supports_backpropagation`.
+    pub(crate) fn backward(
+        &self,
+        inputs: &[f32],
+        sums: &[f32],
+        deltas: &mut [f32],
+        input_gradients: &mut [f32],
+        gradients: &mut [f32],
+    ) {
+        match self {
+            Self::Dense(layer) => layer.backward(inputs, sums, deltas, input_gradients, gradients),
+            Self::Recurrent(layer) => {
+                layer.backward(inputs, sums, deltas, input_gradients, gradients)
+            }
+            Self::Softmax(layer) => layer.backward(sums, deltas, input_gradients),
+            Self::Norm(layer) => layer.backward(inputs, sums, deltas, input_gradients, gradients),
+            _ => panic!(
+                "backpropagation through {:?} layers isn't supported",
+                self.kind()
+            ),
+        }
+    }
 
-            assert_relative_eq!(actual_propagate.as_slice(), expected_propagate.as_slice());
+    /// Forgets the state of stateful layers.
+    pub fn reset(&mut self) {
+        match self {
+            Self::Dense(_) => {}
+            Self::Recurrent(layer) => layer.reset(),
+            Self::Lstm(layer) => layer.reset(),
+            Self::Gru(layer) => layer.reset(),
+            Self::Plastic(layer) => layer.reset(),
+            Self::Softmax(_) | Self::Norm(_) | Self::Conv1d(_) => {}
         }
     }
 }
@@ -0,0 +1,210 @@
+use crate::*;
+
+/// One-dimensional convolution: every channel slides a single kernel (and
+/// bias) over the inputs, so its weights are shared by all positions.
+///
+/// Inputs are zero-padded, so that each channel outputs one value per input
+/// position; outputs are laid out channel by channel.
+#[derive(Clone, Debug)]
+pub struct Conv1dLayer {
+    input_size: usize,
+    kernel: usize,
+    biases: Vec<f32>,
+    /// Row-major `channels x kernel` matrix.
+    weights: Vec<f32>,
+    activation: Activation,
+}
+
+impl Conv1dLayer {
+    pub fn new(
+        input_size: usize,
+        biases: Vec<f32>,
+        weights: Vec<f32>,
+        activation: Activation,
+    ) -> Self {
+        assert!(input_size > 0);
+        assert!(!biases.is_empty());
+        assert_eq!(weights.len() % biases.len(), 0);
+
+        let kernel = weights.len() / biases.len();
+
+        assert!(kernel % 2 == 1, "kernel's size has to be odd");
+
+        Self {
+            input_size,
+            kernel,
+            biases,
+            weights,
+            activation,
+        }
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.channels() * self.input_size
+    }
+
+    pub fn channels(&self) -> usize {
+        self.biases.len()
+    }
+
+    pub fn kernel(&self) -> usize {
+        self.kernel
+    }
+
+    pub fn activation(&self) -> Activation {
+        self.activation
+    }
+
+    pub fn propagate_into(&self, inputs: &[f32], outputs: &mut [f32]) {
+        self.convolve(inputs, outputs);
+        self.activation.apply_all(outputs);
+    }
+
+    /// Like `propagate_into`, but also stores the neurons' values before the
+    /// activation into `sums`.
+    pub fn propagate_traced(&self, inputs: &[f32], sums: &mut [f32], outputs: &mut [f32]) {
+        self.convolve(inputs, sums);
+        outputs.copy_from_slice(sums);
+        self.activation.apply_all(outputs);
+    }
+
+    pub fn propagate_batch(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len() % self.input_size, 0);
+        assert_eq!(
+            inputs.len() / self.input_size * self.output_size(),
+            outputs.len()
+        );
+
+        for (inputs, outputs) in inputs
+            .chunks_exact(self.input_size)
+            .zip(outputs.chunks_exact_mut(self.output_size()))
+        {
+            self.propagate_into(inputs, outputs);
+        }
+    }
+
+    fn convolve(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.input_size);
+
+        let half = self.kernel / 2;
+
+        for ((outputs, bias), kernel) in outputs
+            .chunks_exact_mut(self.input_size)
+            .zip(&self.biases)
+            .zip(self.weights.chunks_exact(self.kernel))
+        {
+            for (position, output) in outputs.iter_mut().enumerate() {
+                *output = *bias;
+
+                for (offset, weight) in kernel.iter().enumerate() {
+                    if let Some(input) = (position + offset)
+                        .checked_sub(half)
+                        .and_then(|idx| inputs.get(idx))
+                    {
+                        *output += weight * input;
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn weight_count(&self) -> usize {
+        self.channels() * (1 + self.kernel)
+    }
+
+    /// Iterates over the parameters in the same order as `weights`.
+    pub(crate) fn weights_mut(&mut self) -> impl Iterator<Item = &mut f32> + '_ {
+        use std::iter::once;
+
+        self.biases
+            .iter_mut()
+            .zip(self.weights.chunks_exact_mut(self.kernel))
+            .flat_map(|(bias, kernel)| once(bias).chain(kernel))
+    }
+
+    /// Iterates over the channels' biases and kernels, bias first.
+    pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        use std::iter::once;
+
+        self.biases
+            .iter()
+            .zip(self.weights.chunks_exact(self.kernel))
+            .flat_map(|(bias, kernel)| once(bias).chain(kernel))
+            .cloned()
+    }
+
+    pub fn from_weights(
+        input_size: usize,
+        channels: usize,
+        kernel: usize,
+        activation: Activation,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Self {
+        let mut biases = Vec::with_capacity(channels);
+        let mut kernels = Vec::with_capacity(channels * kernel);
+
+        for _ in 0..channels {
+            biases.push(weights.next().expect("got not enough weights"));
+
+            for _ in 0..kernel {
+                kernels.push(weights.next().expect("got not enough weights"));
+            }
+        }
+
+        Self::new(input_size, biases, kernels, activation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn propagate() {
+        let layer = Conv1dLayer::new(
+            4,
+            vec![0.0, 1.0],
+            vec![1.0, 2.0, 3.0, 0.0, -1.0, 0.0],
+            Activation::Identity,
+        );
+
+        let mut outputs = [0.0; 8];
+
+        layer.propagate_into(&[1.0, 2.0, 3.0, 4.0], &mut outputs);
+
+        assert_relative_eq!(
+            outputs.as_slice(),
+            [
+                // 1 * x[p - 1] + 2 * x[p] + 3 * x[p + 1]
+                2.0 + 6.0,
+                1.0 + 4.0 + 9.0,
+                2.0 + 6.0 + 12.0,
+                3.0 + 8.0,
+                // 1 - x[p]
+                0.0,
+                -1.0,
+                -2.0,
+                -3.0,
+            ]
+            .as_slice()
+        );
+    }
+
+    #[test]
+    fn is_equivariant_to_shifts() {
+        let layer = Conv1dLayer::new(5, vec![0.5], vec![-1.0, 2.0, 0.5], Activation::Tanh);
+
+        let mut outputs = [0.0; 5];
+        let mut shifted = [0.0; 5];
+
+        layer.propagate_into(&[0.0, 1.0, 0.5, 0.0, 0.0], &mut outputs);
+        layer.propagate_into(&[0.0, 0.0, 1.0, 0.5, 0.0], &mut shifted);
+
+        assert_relative_eq!(&outputs[0..4], &shifted[1..5]);
+    }
+}
@@ -0,0 +1,327 @@
+use crate::*;
+
+/// Fully-connected layer.
+///
+/// Weights are kept as a single row-major `output_size x input_size` matrix,
+/// i.e. row `n` holds the weights of the `n`-th neuron.
+#[derive(Clone, Debug)]
+pub struct DenseLayer {
+    input_size: usize,
+    biases: Vec<f32>,
+    weights: Vec<f32>,
+    activation: Activation,
+}
+
+impl DenseLayer {
+    pub fn new(biases: Vec<f32>, weights: Vec<f32>, activation: Activation) -> Self {
+        assert!(!biases.is_empty());
+        assert_eq!(weights.len() % biases.len(), 0);
+
+        Self {
+            input_size: weights.len() / biases.len(),
+            biases,
+            weights,
+            activation,
+        }
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.biases.len()
+    }
+
+    pub fn activation(&self) -> Activation {
+        self.activation
+    }
+
+    pub fn bias(&self, neuron: usize) -> f32 {
+        self.biases[neuron]
+    }
+
+    /// Weights of the given neuron, one per input.
+    pub fn neuron_weights(&self, neuron: usize) -> &[f32] {
+        &self.weights[neuron * self.input_size..][..self.input_size]
+    }
+
+    pub fn propagate(&self, inputs: &[f32]) -> Vec<f32> {
+        let mut outputs = vec![0.0; self.output_size()];
+        self.propagate_into(inputs, &mut outputs);
+        outputs
+    }
+
+    /// Propagates many inputs at once - `inputs` holds the input vectors one
+    /// after another, and so does `outputs`.
+    pub fn propagate_batch(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len() % self.input_size, 0);
+        assert_eq!(
+            inputs.len() / self.input_size * self.output_size(),
+            outputs.len()
+        );
+
+        for (inputs, outputs) in inputs
+            .chunks_exact(self.input_size)
+            .zip(outputs.chunks_exact_mut(self.output_size()))
+        {
+            self.propagate_into(inputs, outputs);
+        }
+    }
+
+    pub fn propagate_into(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.input_size);
+
+        for ((output, bias), weights) in outputs
+            .iter_mut()
+            .zip(&self.biases)
+            .zip(self.weights.chunks_exact(self.input_size))
+        {
+            *output = bias + math::dot(inputs, weights);
+        }
+
+        self.activation.apply_all(outputs);
+    }
+
+    /// Like `propagate_into`, but also stores the neurons' values before the
+    /// activation into `sums`.
+    pub(crate) fn forward_train(&self, inputs: &[f32], sums: &mut [f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.input_size);
+
+        for ((sum, bias), weights) in sums
+            .iter_mut()
+            .zip(&self.biases)
+            .zip(self.weights.chunks_exact(self.input_size))
+        {
+            *sum = bias + math::dot(inputs, weights);
+        }
+
+        outputs.copy_from_slice(sums);
+        self.activation.apply_all(outputs);
+    }
+
+    /// Given the loss' gradient with respect to the outputs (in `deltas`),
+    /// accumulates its gradient with respect to the parameters (ordered as
+    /// in `weights`) and computes the one with respect to the inputs.
+    pub(crate) fn backward(
+        &self,
+        inputs: &[f32],
+        sums: &[f32],
+        deltas: &mut [f32],
+        input_gradients: &mut [f32],
+        gradients: &mut [f32],
+    ) {
+        input_gradients.fill(0.0);
+
+        for (((delta, sum), weights), gradients) in deltas
+            .iter_mut()
+            .zip(sums)
+            .zip(self.weights.chunks_exact(self.input_size))
+            .zip(gradients.chunks_exact_mut(1 + self.input_size))
+        {
+            *delta *= self.activation.derivative(*sum);
+
+            gradients[0] += *delta;
+
+            for ((gradient, input), (input_gradient, weight)) in gradients[1..]
+                .iter_mut()
+                .zip(inputs)
+                .zip(input_gradients.iter_mut().zip(weights))
+            {
+                *gradient += *delta * input;
+                *input_gradient += *delta * weight;
+            }
+        }
+    }
+
+    pub(crate) fn weight_count(&self) -> usize {
+        self.biases.len() * (1 + self.input_size)
+    }
+
+    /// Iterates over the neurons' biases and weights, in the same order as
+    /// `weights`.
+    pub(crate) fn weights_mut(&mut self) -> impl Iterator<Item = &mut f32> + '_ {
+        use std::iter::once;
+
+        self.biases
+            .iter_mut()
+            .zip(self.weights.chunks_exact_mut(self.input_size))
+            .flat_map(|(bias, weights)| once(bias).chain(weights))
+    }
+
+    /// Iterates over the neurons' biases and weights, bias first.
+    pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        use std::iter::once;
+
+        self.biases
+            .iter()
+            .zip(self.weights.chunks_exact(self.input_size))
+            .flat_map(|(bias, weights)| once(bias).chain(weights))
+            .cloned()
+    }
+
+    pub fn random(
+        rng: &mut dyn RngCore,
+        input_size: usize,
+        output_size: usize,
+        activation: Activation,
+    ) -> Self {
+        Self::from_weights(
+            input_size,
+            output_size,
+            activation,
+            &mut std::iter::repeat_with(|| rng.gen_range(-1.0..=1.0)),
+        )
+    }
+
+    pub fn from_weights(
+        input_size: usize,
+        output_size: usize,
+        activation: Activation,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Self {
+        let mut biases = Vec::with_capacity(output_size);
+        let mut matrix = Vec::with_capacity(output_size * input_size);
+
+        for _ in 0..output_size {
+            biases.push(weights.next().expect("got not enough weights"));
+
+            for _ in 0..input_size {
+                matrix.push(weights.next().expect("got not enough weights"));
+            }
+        }
+
+        Self {
+            input_size,
+            biases,
+            weights: matrix,
+            activation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod random {
+        use super::*;
+        use approx::assert_relative_eq;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let layer = DenseLayer::random(&mut rng, 3, 2, Activation::ReLU);
+
+            let actual_biases: Vec<f32> = (0..2).map(|neuron| layer.bias(neuron)).collect();
+
+            let actual_weights: Vec<&[f32]> =
+                (0..2).map(|neuron| layer.neuron_weights(neuron)).collect();
+
+            let expected_weights: Vec<&[f32]> = vec![
+                &[0.67383957, 0.8181262, 0.26284897],
+                &[-0.53516835, 0.069369674, -0.7648182],
+            ];
+
+            assert_relative_eq!(actual_biases.as_slice(), [-0.6255188, 0.5238807].as_slice());
+            assert_relative_eq!(actual_weights.as_slice(), expected_weights.as_slice());
+        }
+
+        #[test]
+        fn single_neuron() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let layer = DenseLayer::random(&mut rng, 4, 1, Activation::ReLU);
+
+            assert_relative_eq!(layer.bias(0), -0.6255188);
+            assert_relative_eq!(
+                layer.neuron_weights(0),
+                [0.67383957, 0.8181262, 0.26284897, 0.5238807].as_slice()
+            );
+        }
+    }
+
+    mod propagate {
+        use approx::assert_relative_eq;
+
+        use super::*;
+
+        #[test]
+        fn test() {
+            let layer = DenseLayer::new(
+                vec![0.0, 0.0],
+                vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6],
+                Activation::Sigmoid,
+            );
+
+            let actual = layer.propagate(&[0.5, 0.0, 0.5]);
+
+            let expected = [
+                Activation::Sigmoid.apply(0.1 * 0.5 + 0.3 * 0.5),
+                Activation::Sigmoid.apply(0.4 * 0.5 + 0.6 * 0.5),
+            ];
+
+            assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn returns_propagated_input() {
+            let layer = DenseLayer::new(vec![0.5], vec![-0.3, 0.8], Activation::ReLU);
+
+            assert_relative_eq!(layer.propagate(&[-10.0, -10.0])[0], 0.0);
+
+            assert_relative_eq!(
+                layer.propagate(&[0.5, 1.0])[0],
+                (-0.3 * 0.5) + (0.8 * 1.0) + 0.5
+            );
+        }
+
+        #[test]
+        fn applies_activation() {
+            let layer = DenseLayer::new(vec![0.5], vec![-0.3, 0.8], Activation::Tanh);
+
+            assert_relative_eq!(layer.propagate(&[-10.0, -10.0])[0], (-4.5f32).tanh());
+        }
+    }
+
+    mod propagate_batch {
+        use approx::assert_relative_eq;
+
+        use super::*;
+
+        #[test]
+        fn test() {
+            let layer = DenseLayer::new(
+                vec![0.1, -0.2],
+                vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6],
+                Activation::Tanh,
+            );
+
+            let inputs = [[0.5, 0.0, 0.5], [1.0, -1.0, 0.25]];
+            let mut actual = vec![0.0; 4];
+
+            layer.propagate_batch(inputs.as_flattened(), &mut actual);
+
+            let expected: Vec<_> = inputs
+                .iter()
+                .flat_map(|inputs| layer.propagate(inputs))
+                .collect();
+
+            assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+    }
+
+    mod weights {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let layer = DenseLayer::new(vec![1.0, 2.0], vec![0.1, 0.2, 0.3, 0.4], Activation::ReLU);
+            let actual: Vec<_> = layer.weights().collect();
+
+            assert_eq!(actual, vec![1.0, 0.1, 0.2, 2.0, 0.3, 0.4]);
+        }
+    }
+}
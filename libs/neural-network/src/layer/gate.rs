@@ -0,0 +1,157 @@
+use crate::*;
+
+/// Weights of a recurrent layer's gate: each neuron gets a bias, one weight
+/// per input and one weight per layer's previous output.
+#[derive(Clone, Debug)]
+pub(crate) struct Gate {
+    input_size: usize,
+    biases: Vec<f32>,
+    input_weights: Vec<f32>,
+    recurrent_weights: Vec<f32>,
+}
+
+impl Gate {
+    pub(crate) fn new(
+        biases: Vec<f32>,
+        input_weights: Vec<f32>,
+        recurrent_weights: Vec<f32>,
+    ) -> Self {
+        assert!(!biases.is_empty());
+        assert_eq!(input_weights.len() % biases.len(), 0);
+        assert_eq!(recurrent_weights.len(), biases.len() * biases.len());
+
+        Self {
+            input_size: input_weights.len() / biases.len(),
+            biases,
+            input_weights,
+            recurrent_weights,
+        }
+    }
+
+    pub(crate) fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    pub(crate) fn output_size(&self) -> usize {
+        self.biases.len()
+    }
+
+    /// Bias plus the weighted inputs of given neuron.
+    pub(crate) fn input(&self, neuron: usize, inputs: &[f32]) -> f32 {
+        let weights = &self.input_weights[neuron * self.input_size..][..self.input_size];
+
+        self.biases[neuron] + math::dot(inputs, weights)
+    }
+
+    /// Weighted previous outputs of given neuron.
+    pub(crate) fn recurrent(&self, neuron: usize, state: &[f32]) -> f32 {
+        let size = self.output_size();
+        let weights = &self.recurrent_weights[neuron * size..][..size];
+
+        math::dot(state, weights)
+    }
+
+    pub(crate) fn sum(&self, neuron: usize, inputs: &[f32], state: &[f32]) -> f32 {
+        self.input(neuron, inputs) + self.recurrent(neuron, state)
+    }
+
+    /// Backpropagates through the gate, treating `state` as a constant -
+    /// see `DenseLayer::backward`, whose `deltas` are expected here already
+    /// multiplied by the activation's derivative.
+    pub(crate) fn backward(
+        &self,
+        inputs: &[f32],
+        state: &[f32],
+        deltas: &[f32],
+        input_gradients: &mut [f32],
+        gradients: &mut [f32],
+    ) {
+        let size = self.output_size();
+
+        input_gradients.fill(0.0);
+
+        for (neuron, (delta, gradients)) in deltas
+            .iter()
+            .zip(gradients.chunks_exact_mut(1 + self.input_size + size))
+            .enumerate()
+        {
+            let input_weights = &self.input_weights[neuron * self.input_size..][..self.input_size];
+            let (bias, gradients) = gradients.split_first_mut().unwrap();
+            let (input_weight_gradients, recurrent_weight_gradients) =
+                gradients.split_at_mut(self.input_size);
+
+            *bias += delta;
+
+            for ((gradient, input), (input_gradient, weight)) in input_weight_gradients
+                .iter_mut()
+                .zip(inputs)
+                .zip(input_gradients.iter_mut().zip(input_weights))
+            {
+                *gradient += delta * input;
+                *input_gradient += delta * weight;
+            }
+
+            for (gradient, state) in recurrent_weight_gradients.iter_mut().zip(state) {
+                *gradient += delta * state;
+            }
+        }
+    }
+
+    pub(crate) fn weight_count(&self) -> usize {
+        self.output_size() * (1 + self.input_size + self.output_size())
+    }
+
+    /// Iterates over the neurons' parameters, in the same order as `weights`.
+    pub(crate) fn weights_mut(&mut self) -> impl Iterator<Item = &mut f32> + '_ {
+        use std::iter::once;
+
+        let size = self.output_size();
+
+        self.biases
+            .iter_mut()
+            .zip(self.input_weights.chunks_exact_mut(self.input_size))
+            .zip(self.recurrent_weights.chunks_exact_mut(size))
+            .flat_map(|((bias, input_weights), recurrent_weights)| {
+                once(bias).chain(input_weights).chain(recurrent_weights)
+            })
+    }
+
+    /// Iterates over the neurons' parameters: bias, input weights and then
+    /// recurrent weights.
+    pub(crate) fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        use std::iter::once;
+
+        self.biases
+            .iter()
+            .zip(self.input_weights.chunks_exact(self.input_size))
+            .zip(self.recurrent_weights.chunks_exact(self.output_size()))
+            .flat_map(|((bias, input_weights), recurrent_weights)| {
+                once(bias).chain(input_weights).chain(recurrent_weights)
+            })
+            .cloned()
+    }
+
+    pub(crate) fn from_weights(
+        input_size: usize,
+        output_size: usize,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Self {
+        let mut biases = Vec::with_capacity(output_size);
+        let mut input_weights = Vec::with_capacity(output_size * input_size);
+        let mut recurrent_weights = Vec::with_capacity(output_size * output_size);
+
+        for _ in 0..output_size {
+            biases.push(weights.next().expect("got not enough weights"));
+
+            for _ in 0..input_size {
+                input_weights.push(weights.next().expect("got not enough weights"));
+            }
+
+            for _ in 0..output_size {
+                recurrent_weights.push(weights.next().expect("got not enough weights"));
+            }
+        }
+
+        Self::new(biases, input_weights, recurrent_weights)
+    }
+}
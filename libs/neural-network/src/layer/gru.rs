@@ -0,0 +1,261 @@
+use super::gate::Gate;
+use crate::*;
+
+/// Gated recurrent unit layer.
+///
+/// ```text
+/// candidate = activation(W x + b + reset * (U h))
+/// h = (1 - update) * candidate + update * h
+/// ```
+///
+/// The update and reset gates always use the sigmoid; `activation` (tanh,
+/// traditionally) is used for the candidate.
+#[derive(Clone, Debug)]
+pub struct GruLayer {
+    update_gate: Gate,
+    reset_gate: Gate,
+    candidate: Gate,
+    activation: Activation,
+    state: Vec<f32>,
+}
+
+impl GruLayer {
+    fn new(update_gate: Gate, reset_gate: Gate, candidate: Gate, activation: Activation) -> Self {
+        Self {
+            state: vec![0.0; update_gate.output_size()],
+            update_gate,
+            reset_gate,
+            candidate,
+            activation,
+        }
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.update_gate.input_size()
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.update_gate.output_size()
+    }
+
+    pub fn activation(&self) -> Activation {
+        self.activation
+    }
+
+    /// Outputs from the previous `propagate_into` call.
+    pub fn state(&self) -> &[f32] {
+        &self.state
+    }
+
+    pub fn reset(&mut self) {
+        self.state.fill(0.0);
+    }
+
+    pub fn propagate_into(&mut self, inputs: &[f32], outputs: &mut [f32]) {
+        self.forward(inputs, outputs);
+        self.state.copy_from_slice(outputs);
+    }
+
+    /// Like `propagate_into`, but also stores the candidate's values before
+    /// the activation into `sums`.
+    pub fn propagate_traced(&mut self, inputs: &[f32], sums: &mut [f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.input_size());
+
+        for (neuron, (sum, output)) in sums.iter_mut().zip(outputs.iter_mut()).enumerate() {
+            (*sum, *output) = self.neuron(neuron, inputs);
+        }
+
+        self.state.copy_from_slice(outputs);
+    }
+
+    pub fn propagate_batch(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len() % self.input_size(), 0);
+        assert_eq!(
+            inputs.len() / self.input_size() * self.output_size(),
+            outputs.len()
+        );
+
+        for (inputs, outputs) in inputs
+            .chunks_exact(self.input_size())
+            .zip(outputs.chunks_exact_mut(self.output_size()))
+        {
+            self.forward(inputs, outputs);
+        }
+    }
+
+    fn forward(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.input_size());
+
+        for (neuron, output) in outputs.iter_mut().enumerate() {
+            *output = self.neuron(neuron, inputs).1;
+        }
+    }
+
+    /// Returns the candidate's value before the activation and the neuron's
+    /// output.
+    fn neuron(&self, neuron: usize, inputs: &[f32]) -> (f32, f32) {
+        let sigmoid = |x| Activation::Sigmoid.apply(x);
+
+        let update = sigmoid(self.update_gate.sum(neuron, inputs, &self.state));
+        let reset = sigmoid(self.reset_gate.sum(neuron, inputs, &self.state));
+
+        let sum = self.candidate.input(neuron, inputs)
+            + reset * self.candidate.recurrent(neuron, &self.state);
+
+        let candidate = self.activation.apply(sum);
+
+        (
+            sum,
+            (1.0 - update) * candidate + update * self.state[neuron],
+        )
+    }
+
+    pub(crate) fn weight_count(&self) -> usize {
+        self.update_gate.weight_count()
+            + self.reset_gate.weight_count()
+            + self.candidate.weight_count()
+    }
+
+    pub(crate) fn weights_mut(&mut self) -> impl Iterator<Item = &mut f32> + '_ {
+        self.update_gate
+            .weights_mut()
+            .chain(self.reset_gate.weights_mut())
+            .chain(self.candidate.weights_mut())
+    }
+
+    /// Iterates over the gates' parameters - update gate, reset gate,
+    /// candidate - each of them neuron by neuron: bias, input weights and
+    /// then recurrent weights.
+    pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        self.update_gate
+            .weights()
+            .chain(self.reset_gate.weights())
+            .chain(self.candidate.weights())
+    }
+
+    pub fn random(
+        rng: &mut dyn RngCore,
+        input_size: usize,
+        output_size: usize,
+        activation: Activation,
+    ) -> Self {
+        Self::from_weights(
+            input_size,
+            output_size,
+            activation,
+            &mut std::iter::repeat_with(|| rng.gen_range(-1.0..=1.0)),
+        )
+    }
+
+    pub fn from_weights(
+        input_size: usize,
+        output_size: usize,
+        activation: Activation,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Self {
+        let mut gate = || Gate::from_weights(input_size, output_size, weights);
+
+        Self::new(gate(), gate(), gate(), activation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn sigmoid(x: f32) -> f32 {
+        1.0 / (1.0 + (-x).exp())
+    }
+
+    /// Single neuron with a single input; per gate: bias, input weight and
+    /// recurrent weight.
+    fn layer() -> GruLayer {
+        GruLayer::from_weights(
+            1,
+            1,
+            Activation::Tanh,
+            &mut [0.1, 0.5, -0.2, 0.3, 0.4, 0.6, 0.0, 0.8, 0.9].into_iter(),
+        )
+    }
+
+    mod propagate_into {
+        use super::*;
+
+        #[test]
+        fn remembers_previous_outputs() {
+            let mut layer = layer();
+            let mut outputs = [0.0];
+            let mut expected = 0.0;
+
+            for x in [1.0, 0.5, -1.0] {
+                layer.propagate_into(&[x], &mut outputs);
+
+                let h = expected;
+                let update = sigmoid(0.1 + 0.5 * x - 0.2 * h);
+                let reset = sigmoid(0.3 + 0.4 * x + 0.6 * h);
+                let candidate = (0.0 + 0.8 * x + reset * 0.9 * h).tanh();
+
+                expected = (1.0 - update) * candidate + update * h;
+
+                assert_relative_eq!(outputs[0], expected);
+                assert_relative_eq!(layer.state()[0], expected);
+            }
+        }
+
+        #[test]
+        fn forgets_after_reset() {
+            let mut layer = layer();
+            let mut first = [0.0];
+            let mut second = [0.0];
+
+            layer.propagate_into(&[1.0], &mut first);
+            layer.reset();
+            layer.propagate_into(&[1.0], &mut second);
+
+            assert_relative_eq!(first[0], second[0]);
+        }
+    }
+
+    mod propagate_batch {
+        use super::*;
+
+        #[test]
+        fn keeps_state_intact() {
+            let mut layer = layer();
+            let mut outputs = [0.0];
+            layer.propagate_into(&[1.0], &mut outputs);
+
+            let mut batch = [0.0; 2];
+            layer.propagate_batch(&[0.5, -1.0], &mut batch);
+
+            assert_relative_eq!(layer.state()[0], outputs[0]);
+
+            for (x, actual) in [0.5, -1.0].into_iter().zip(batch) {
+                let mut expected = [0.0];
+                layer.clone().propagate_into(&[x], &mut expected);
+
+                assert_relative_eq!(actual, expected[0]);
+            }
+        }
+    }
+
+    mod weights {
+        use super::*;
+
+        #[test]
+        fn round_trips_through_from_weights() {
+            let layer = GruLayer::from_weights(
+                2,
+                3,
+                Activation::Tanh,
+                &mut (0..3 * 3 * (1 + 2 + 3)).map(|n| n as f32),
+            );
+
+            let weights: Vec<_> = layer.weights().collect();
+            let expected: Vec<_> = (0..54).map(|n| n as f32).collect();
+
+            assert_eq!(weights, expected);
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use crate::*;
+
+/// Normalizes its inputs to zero mean and unit variance, then scales and
+/// shifts each of them by its own gain and bias:
+///
+/// ```text
+/// y = activation(gain * (x - mean) / sqrt(variance + epsilon) + bias)
+/// ```
+#[derive(Clone, Debug)]
+pub struct LayerNormLayer {
+    biases: Vec<f32>,
+    gains: Vec<f32>,
+    activation: Activation,
+}
+
+impl LayerNormLayer {
+    pub const EPSILON: f32 = 1e-5;
+
+    pub fn new(biases: Vec<f32>, gains: Vec<f32>, activation: Activation) -> Self {
+        assert!(!biases.is_empty());
+        assert_eq!(biases.len(), gains.len());
+
+        Self {
+            biases,
+            gains,
+            activation,
+        }
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.biases.len()
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.biases.len()
+    }
+
+    pub fn activation(&self) -> Activation {
+        self.activation
+    }
+
+    pub fn propagate_into(&self, inputs: &[f32], outputs: &mut [f32]) {
+        self.normalize(inputs, outputs);
+        self.activation.apply_all(outputs);
+    }
+
+    /// Like `propagate_into`, but also stores the neurons' values before the
+    /// activation into `sums`.
+    pub fn propagate_traced(&self, inputs: &[f32], sums: &mut [f32], outputs: &mut [f32]) {
+        self.normalize(inputs, sums);
+        outputs.copy_from_slice(sums);
+        self.activation.apply_all(outputs);
+    }
+
+    /// See `DenseLayer::backward`.
+    pub(crate) fn backward(
+        &self,
+        inputs: &[f32],
+        sums: &[f32],
+        deltas: &mut [f32],
+        input_gradients: &mut [f32],
+        gradients: &mut [f32],
+    ) {
+        let (mean, std) = Self::moments(inputs);
+        let len = inputs.len() as f32;
+
+        // Gradients with respect to the normalized inputs, and their means
+        // (plain and weighted by the normalized inputs)
+        let mut normalized_deltas = vec![0.0; inputs.len()];
+        let (mut delta_mean, mut delta_dot) = (0.0, 0.0);
+
+        for (idx, (delta, sum)) in deltas.iter_mut().zip(sums).enumerate() {
+            let normalized = (inputs[idx] - mean) / std;
+
+            *delta *= self.activation.derivative(*sum);
+
+            gradients[2 * idx] += *delta;
+            gradients[2 * idx + 1] += *delta * normalized;
+
+            normalized_deltas[idx] = *delta * self.gains[idx];
+            delta_mean += normalized_deltas[idx] / len;
+            delta_dot += normalized_deltas[idx] * normalized / len;
+        }
+
+        for ((input_gradient, normalized_delta), input) in input_gradients
+            .iter_mut()
+            .zip(normalized_deltas)
+            .zip(inputs)
+        {
+            let normalized = (input - mean) / std;
+
+            *input_gradient = (normalized_delta - delta_mean - normalized * delta_dot) / std;
+        }
+    }
+
+    pub fn propagate_batch(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), outputs.len());
+
+        for (inputs, outputs) in inputs
+            .chunks_exact(self.input_size())
+            .zip(outputs.chunks_exact_mut(self.output_size()))
+        {
+            self.propagate_into(inputs, outputs);
+        }
+    }
+
+    fn normalize(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.input_size());
+
+        let (mean, std) = Self::moments(inputs);
+
+        for (((output, input), gain), bias) in outputs
+            .iter_mut()
+            .zip(inputs)
+            .zip(&self.gains)
+            .zip(&self.biases)
+        {
+            *output = gain * (input - mean) / std + bias;
+        }
+    }
+
+    /// Returns the inputs' mean and standard deviation (with `EPSILON`
+    /// added to the variance).
+    fn moments(inputs: &[f32]) -> (f32, f32) {
+        let len = inputs.len() as f32;
+        let mean = inputs.iter().sum::<f32>() / len;
+        let variance = inputs.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / len;
+
+        (mean, (variance + Self::EPSILON).sqrt())
+    }
+
+    pub(crate) fn weight_count(&self) -> usize {
+        2 * self.biases.len()
+    }
+
+    /// Iterates over the parameters in the same order as `weights`.
+    pub(crate) fn weights_mut(&mut self) -> impl Iterator<Item = &mut f32> + '_ {
+        self.biases
+            .iter_mut()
+            .zip(&mut self.gains)
+            .flat_map(|(bias, gain)| [bias, gain])
+    }
+
+    /// Iterates over the neurons' biases and gains, bias first.
+    pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        self.biases
+            .iter()
+            .zip(&self.gains)
+            .flat_map(|(&bias, &gain)| [bias, gain])
+    }
+
+    pub fn from_weights(
+        size: usize,
+        activation: Activation,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Self {
+        let mut biases = Vec::with_capacity(size);
+        let mut gains = Vec::with_capacity(size);
+
+        for _ in 0..size {
+            biases.push(weights.next().expect("got not enough weights"));
+            gains.push(weights.next().expect("got not enough weights"));
+        }
+
+        Self::new(biases, gains, activation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn propagate() {
+        let layer = LayerNormLayer::new(
+            vec![0.0, 1.0, 0.0],
+            vec![1.0, 1.0, 2.0],
+            Activation::Identity,
+        );
+
+        let mut outputs = [0.0; 3];
+
+        layer.propagate_into(&[1.0, 2.0, 3.0], &mut outputs);
+
+        // mean = 2, variance = 2/3
+        let std = (2.0f32 / 3.0 + LayerNormLayer::EPSILON).sqrt();
+
+        let expected = [-1.0 / std, 1.0, 2.0 / std];
+
+        assert_relative_eq!(outputs.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn backward() {
+        let layer =
+            LayerNormLayer::new(vec![0.1, -0.2, 0.3], vec![1.0, 0.5, -2.0], Activation::Tanh);
+
+        let (inputs, targets) = ([1.0, -0.5, 2.0], [1.0, 0.0, -2.0]);
+
+        let mut sums = [0.0; 3];
+        let mut outputs = [0.0; 3];
+        let mut deltas = targets;
+        let mut input_gradients = [0.0; 3];
+        let mut gradients = [0.0; 6];
+
+        layer.propagate_traced(&inputs, &mut sums, &mut outputs);
+        layer.backward(
+            &inputs,
+            &sums,
+            &mut deltas,
+            &mut input_gradients,
+            &mut gradients,
+        );
+
+        // loss = dot(targets, layer(inputs)), differentiated numerically
+        let loss = |layer: &LayerNormLayer, inputs: &[f32]| {
+            let mut outputs = [0.0; 3];
+            layer.propagate_into(inputs, &mut outputs);
+            math::dot(&targets, &outputs)
+        };
+
+        for input in 0..3 {
+            let (mut plus, mut minus) = (inputs, inputs);
+
+            plus[input] += 1e-2;
+            minus[input] -= 1e-2;
+
+            assert_relative_eq!(
+                input_gradients[input],
+                (loss(&layer, &plus) - loss(&layer, &minus)) / 2e-2,
+                epsilon = 1e-2
+            );
+        }
+
+        for (weight, gradient) in gradients.into_iter().enumerate() {
+            let (mut plus, mut minus) = (layer.clone(), layer.clone());
+
+            *plus.weights_mut().nth(weight).unwrap() += 1e-2;
+            *minus.weights_mut().nth(weight).unwrap() -= 1e-2;
+
+            assert_relative_eq!(
+                gradient,
+                (loss(&plus, &inputs) - loss(&minus, &inputs)) / 2e-2,
+                epsilon = 1e-2
+            );
+        }
+    }
+
+    #[test]
+    fn weights() {
+        let weights = [0.1, 0.2, 0.3, 0.4];
+        let mut layer =
+            LayerNormLayer::from_weights(2, Activation::ReLU, &mut weights.iter().cloned());
+
+        assert_eq!(layer.weights().collect::<Vec<_>>(), weights);
+        assert_eq!(layer.weights_mut().map(|w| *w).collect::<Vec<_>>(), weights);
+    }
+}
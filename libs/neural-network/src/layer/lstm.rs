@@ -0,0 +1,280 @@
+use super::gate::Gate;
+use crate::*;
+
+/// Long short-term memory layer.
+///
+/// Every neuron keeps a cell, which its input and forget gates write into
+/// and its output gate reads from:
+///
+/// ```text
+/// c = forget * c + input * activation(candidate)
+/// h = output * activation(c)
+/// ```
+///
+/// The gates always use the sigmoid; `activation` (tanh, traditionally) is
+/// used for the candidate and the cell's output.
+#[derive(Clone, Debug)]
+pub struct LstmLayer {
+    input_gate: Gate,
+    forget_gate: Gate,
+    candidate: Gate,
+    output_gate: Gate,
+    activation: Activation,
+    hidden: Vec<f32>,
+    cell: Vec<f32>,
+}
+
+impl LstmLayer {
+    fn new(
+        input_gate: Gate,
+        forget_gate: Gate,
+        candidate: Gate,
+        output_gate: Gate,
+        activation: Activation,
+    ) -> Self {
+        let size = input_gate.output_size();
+
+        Self {
+            input_gate,
+            forget_gate,
+            candidate,
+            output_gate,
+            activation,
+            hidden: vec![0.0; size],
+            cell: vec![0.0; size],
+        }
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.input_gate.input_size()
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.input_gate.output_size()
+    }
+
+    pub fn activation(&self) -> Activation {
+        self.activation
+    }
+
+    /// Outputs from the previous `propagate_into` call.
+    pub fn hidden(&self) -> &[f32] {
+        &self.hidden
+    }
+
+    pub fn cell(&self) -> &[f32] {
+        &self.cell
+    }
+
+    pub fn reset(&mut self) {
+        self.hidden.fill(0.0);
+        self.cell.fill(0.0);
+    }
+
+    pub fn propagate_into(&mut self, inputs: &[f32], outputs: &mut [f32]) {
+        let mut cell = std::mem::take(&mut self.cell);
+
+        self.forward(inputs, &mut cell, outputs);
+        self.cell = cell;
+        self.hidden.copy_from_slice(outputs);
+    }
+
+    /// Like `propagate_into`, but also stores the new cell state (i.e. the
+    /// value passed to the output's activation) into `sums`.
+    pub fn propagate_traced(&mut self, inputs: &[f32], sums: &mut [f32], outputs: &mut [f32]) {
+        self.propagate_into(inputs, outputs);
+        sums.copy_from_slice(&self.cell);
+    }
+
+    pub fn propagate_batch(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len() % self.input_size(), 0);
+        assert_eq!(
+            inputs.len() / self.input_size() * self.output_size(),
+            outputs.len()
+        );
+
+        let mut cell = self.cell.clone();
+
+        for (inputs, outputs) in inputs
+            .chunks_exact(self.input_size())
+            .zip(outputs.chunks_exact_mut(self.output_size()))
+        {
+            cell.copy_from_slice(&self.cell);
+            self.forward(inputs, &mut cell, outputs);
+        }
+    }
+
+    fn forward(&self, inputs: &[f32], cell: &mut [f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.input_size());
+
+        let sigmoid = |x| Activation::Sigmoid.apply(x);
+
+        for (neuron, (cell, output)) in cell.iter_mut().zip(outputs).enumerate() {
+            let input = sigmoid(self.input_gate.sum(neuron, inputs, &self.hidden));
+            let forget = sigmoid(self.forget_gate.sum(neuron, inputs, &self.hidden));
+            let candidate = self
+                .activation
+                .apply(self.candidate.sum(neuron, inputs, &self.hidden));
+            let output_gate = sigmoid(self.output_gate.sum(neuron, inputs, &self.hidden));
+
+            *cell = forget * *cell + input * candidate;
+            *output = output_gate * self.activation.apply(*cell);
+        }
+    }
+
+    pub(crate) fn weight_count(&self) -> usize {
+        self.input_gate.weight_count()
+            + self.forget_gate.weight_count()
+            + self.candidate.weight_count()
+            + self.output_gate.weight_count()
+    }
+
+    pub(crate) fn weights_mut(&mut self) -> impl Iterator<Item = &mut f32> + '_ {
+        self.input_gate
+            .weights_mut()
+            .chain(self.forget_gate.weights_mut())
+            .chain(self.candidate.weights_mut())
+            .chain(self.output_gate.weights_mut())
+    }
+
+    /// Iterates over the gates' parameters - input gate, forget gate,
+    /// candidate, output gate - each of them neuron by neuron: bias, input
+    /// weights and then recurrent weights.
+    pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        self.input_gate
+            .weights()
+            .chain(self.forget_gate.weights())
+            .chain(self.candidate.weights())
+            .chain(self.output_gate.weights())
+    }
+
+    pub fn random(
+        rng: &mut dyn RngCore,
+        input_size: usize,
+        output_size: usize,
+        activation: Activation,
+    ) -> Self {
+        Self::from_weights(
+            input_size,
+            output_size,
+            activation,
+            &mut std::iter::repeat_with(|| rng.gen_range(-1.0..=1.0)),
+        )
+    }
+
+    pub fn from_weights(
+        input_size: usize,
+        output_size: usize,
+        activation: Activation,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Self {
+        let mut gate = || Gate::from_weights(input_size, output_size, weights);
+
+        Self::new(gate(), gate(), gate(), gate(), activation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn sigmoid(x: f32) -> f32 {
+        1.0 / (1.0 + (-x).exp())
+    }
+
+    /// Single neuron with a single input; per gate: bias, input weight and
+    /// recurrent weight.
+    fn layer() -> LstmLayer {
+        LstmLayer::from_weights(
+            1,
+            1,
+            Activation::Tanh,
+            &mut [0.1, 0.5, -0.2, 1.0, 0.3, 0.4, 0.0, 0.8, 0.6, -0.1, 0.2, 0.7].into_iter(),
+        )
+    }
+
+    mod propagate_into {
+        use super::*;
+
+        #[test]
+        fn remembers_previous_outputs() {
+            let mut layer = layer();
+            let mut outputs = [0.0];
+            let mut expected_cell = 0.0;
+            let mut expected_hidden = 0.0;
+
+            for x in [1.0, 0.5, -1.0] {
+                layer.propagate_into(&[x], &mut outputs);
+
+                let h = expected_hidden;
+                let input = sigmoid(0.1 + 0.5 * x - 0.2 * h);
+                let forget = sigmoid(1.0 + 0.3 * x + 0.4 * h);
+                let candidate = (0.0 + 0.8 * x + 0.6 * h).tanh();
+                let output = sigmoid(-0.1 + 0.2 * x + 0.7 * h);
+
+                expected_cell = forget * expected_cell + input * candidate;
+                expected_hidden = output * f32::tanh(expected_cell);
+
+                assert_relative_eq!(outputs[0], expected_hidden);
+                assert_relative_eq!(layer.cell()[0], expected_cell);
+                assert_relative_eq!(layer.hidden()[0], expected_hidden);
+            }
+        }
+
+        #[test]
+        fn forgets_after_reset() {
+            let mut layer = layer();
+            let mut first = [0.0];
+            let mut second = [0.0];
+
+            layer.propagate_into(&[1.0], &mut first);
+            layer.reset();
+            layer.propagate_into(&[1.0], &mut second);
+
+            assert_relative_eq!(first[0], second[0]);
+        }
+    }
+
+    mod propagate_batch {
+        use super::*;
+
+        #[test]
+        fn keeps_state_intact() {
+            let mut layer = layer();
+            let mut outputs = [0.0];
+            layer.propagate_into(&[1.0], &mut outputs);
+
+            let mut batch = [0.0; 2];
+            layer.propagate_batch(&[0.5, -1.0], &mut batch);
+
+            assert_relative_eq!(layer.hidden()[0], outputs[0]);
+
+            for (x, actual) in [0.5, -1.0].into_iter().zip(batch) {
+                let mut expected = [0.0];
+                layer.clone().propagate_into(&[x], &mut expected);
+
+                assert_relative_eq!(actual, expected[0]);
+            }
+        }
+    }
+
+    mod weights {
+        use super::*;
+
+        #[test]
+        fn round_trips_through_from_weights() {
+            let layer = LstmLayer::from_weights(
+                2,
+                3,
+                Activation::Tanh,
+                &mut (0..4 * 3 * (1 + 2 + 3)).map(|n| n as f32),
+            );
+
+            let weights: Vec<_> = layer.weights().collect();
+            let expected: Vec<_> = (0..72).map(|n| n as f32).collect();
+
+            assert_eq!(weights, expected);
+        }
+    }
+}
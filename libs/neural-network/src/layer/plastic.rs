@@ -0,0 +1,350 @@
+use crate::*;
+
+/// Fully-connected layer whose weights keep changing as it propagates,
+/// following a Hebbian rule with separate coefficients for every connection
+/// (the "ABCD" rule):
+///
+/// ```text
+/// w += eta * (a * x * y + b * x + c * y + d)
+/// ```
+///
+/// ... where `x` is the connection's input, `y` is the neuron's output and
+/// `eta` is the neuron's learning rate.
+///
+/// The changes are kept apart from the evolved weights (which is what
+/// `weights` returns) and are forgotten on `reset`.
+#[derive(Clone, Debug)]
+pub struct PlasticLayer {
+    input_size: usize,
+    biases: Vec<f32>,
+    weights: Vec<f32>,
+    learning_rates: Vec<f32>,
+    /// `[a, b, c, d]` of each connection, laid out like `weights`.
+    rules: Vec<[f32; 4]>,
+    activation: Activation,
+    /// Changes accumulated since the last `reset`, laid out like `weights`.
+    deltas: Vec<f32>,
+}
+
+impl PlasticLayer {
+    /// Keeps runaway Hebbian growth from overflowing the weights.
+    pub const WEIGHT_LIMIT: f32 = 10.0;
+
+    pub fn new(
+        biases: Vec<f32>,
+        weights: Vec<f32>,
+        learning_rates: Vec<f32>,
+        rules: Vec<[f32; 4]>,
+        activation: Activation,
+    ) -> Self {
+        assert!(!biases.is_empty());
+        assert_eq!(weights.len() % biases.len(), 0);
+        assert_eq!(learning_rates.len(), biases.len());
+        assert_eq!(rules.len(), weights.len());
+
+        Self {
+            input_size: weights.len() / biases.len(),
+            deltas: vec![0.0; weights.len()],
+            biases,
+            weights,
+            learning_rates,
+            rules,
+            activation,
+        }
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.biases.len()
+    }
+
+    pub fn activation(&self) -> Activation {
+        self.activation
+    }
+
+    /// Weights as they are right now, i.e. including the changes made since
+    /// the last `reset`.
+    pub fn current_weights(&self) -> impl Iterator<Item = f32> + '_ {
+        self.weights
+            .iter()
+            .zip(&self.deltas)
+            .map(|(weight, delta)| (weight + delta).clamp(-Self::WEIGHT_LIMIT, Self::WEIGHT_LIMIT))
+    }
+
+    pub fn reset(&mut self) {
+        self.deltas.fill(0.0);
+    }
+
+    pub fn propagate_into(&mut self, inputs: &[f32], outputs: &mut [f32]) {
+        self.forward(inputs, outputs);
+        self.activation.apply_all(outputs);
+        self.learn(inputs, outputs);
+    }
+
+    /// Like `propagate_into`, but also stores the neurons' values before the
+    /// activation into `sums`.
+    pub fn propagate_traced(&mut self, inputs: &[f32], sums: &mut [f32], outputs: &mut [f32]) {
+        self.forward(inputs, sums);
+        outputs.copy_from_slice(sums);
+        self.activation.apply_all(outputs);
+        self.learn(inputs, outputs);
+    }
+
+    /// Propagates many inputs at once, using the current weights - which
+    /// are left unchanged.
+    pub fn propagate_batch(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len() % self.input_size, 0);
+        assert_eq!(
+            inputs.len() / self.input_size * self.output_size(),
+            outputs.len()
+        );
+
+        for (inputs, outputs) in inputs
+            .chunks_exact(self.input_size)
+            .zip(outputs.chunks_exact_mut(self.output_size()))
+        {
+            self.forward(inputs, outputs);
+            self.activation.apply_all(outputs);
+        }
+    }
+
+    /// Stores the neurons' values before the activation into `sums`.
+    fn forward(&self, inputs: &[f32], sums: &mut [f32]) {
+        assert_eq!(inputs.len(), self.input_size);
+
+        let mut weights = self.current_weights();
+
+        for (sum, bias) in sums.iter_mut().zip(&self.biases) {
+            *sum = bias
+                + inputs
+                    .iter()
+                    .zip(weights.by_ref())
+                    .map(|(input, weight)| input * weight)
+                    .sum::<f32>();
+        }
+    }
+
+    fn learn(&mut self, inputs: &[f32], outputs: &[f32]) {
+        for ((((deltas, weights), rules), &eta), &y) in self
+            .deltas
+            .chunks_exact_mut(self.input_size)
+            .zip(self.weights.chunks_exact(self.input_size))
+            .zip(self.rules.chunks_exact(self.input_size))
+            .zip(&self.learning_rates)
+            .zip(outputs)
+        {
+            for (((delta, weight), [a, b, c, d]), &x) in
+                deltas.iter_mut().zip(weights).zip(rules).zip(inputs)
+            {
+                // Deltas saturate along with the weights, so that changes in
+                // the opposite direction take effect right away
+                *delta = (*delta + eta * (a * x * y + b * x + c * y + d))
+                    .clamp(-Self::WEIGHT_LIMIT - weight, Self::WEIGHT_LIMIT - weight);
+            }
+        }
+    }
+
+    pub(crate) fn weight_count(&self) -> usize {
+        self.biases.len() * (2 + 5 * self.input_size)
+    }
+
+    /// Iterates over the parameters in the same order as `weights`.
+    pub(crate) fn weights_mut(&mut self) -> impl Iterator<Item = &mut f32> + '_ {
+        use std::iter::once;
+
+        let input_size = self.input_size;
+
+        let weights = self
+            .biases
+            .iter_mut()
+            .zip(self.weights.chunks_exact_mut(input_size))
+            .flat_map(|(bias, weights)| once(bias).chain(weights));
+
+        let rules = self
+            .learning_rates
+            .iter_mut()
+            .zip(self.rules.chunks_exact_mut(input_size))
+            .flat_map(|(eta, rules)| once(eta).chain(rules.iter_mut().flatten()));
+
+        weights.chain(rules)
+    }
+
+    /// Iterates over the neurons' biases and (evolved) weights, bias first,
+    /// and then over the neurons' learning rates and rules, learning rate
+    /// first.
+    pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        use std::iter::once;
+
+        let weights = self
+            .biases
+            .iter()
+            .zip(self.weights.chunks_exact(self.input_size))
+            .flat_map(|(bias, weights)| once(bias).chain(weights));
+
+        let rules = self
+            .learning_rates
+            .iter()
+            .zip(self.rules.chunks_exact(self.input_size))
+            .flat_map(|(eta, rules)| once(eta).chain(rules.iter().flatten()));
+
+        weights.chain(rules).cloned()
+    }
+
+    pub fn from_weights(
+        input_size: usize,
+        output_size: usize,
+        activation: Activation,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Self {
+        let mut next = || weights.next().expect("got not enough weights");
+
+        let mut biases = Vec::with_capacity(output_size);
+        let mut matrix = Vec::with_capacity(output_size * input_size);
+
+        for _ in 0..output_size {
+            biases.push(next());
+
+            for _ in 0..input_size {
+                matrix.push(next());
+            }
+        }
+
+        let mut learning_rates = Vec::with_capacity(output_size);
+        let mut rules = Vec::with_capacity(output_size * input_size);
+
+        for _ in 0..output_size {
+            learning_rates.push(next());
+
+            for _ in 0..input_size {
+                rules.push([next(), next(), next(), next()]);
+            }
+        }
+
+        Self::new(biases, matrix, learning_rates, rules, activation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn layer() -> PlasticLayer {
+        PlasticLayer::new(
+            vec![0.0],
+            vec![0.5, -0.5],
+            vec![0.1],
+            vec![[1.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, -1.0]],
+            Activation::Identity,
+        )
+    }
+
+    mod propagate {
+        use super::*;
+
+        #[test]
+        fn learns() {
+            let mut layer = layer();
+            let mut outputs = [0.0];
+
+            layer.propagate_into(&[1.0, 2.0], &mut outputs);
+
+            // y = 0.5 - 1.0
+            assert_relative_eq!(outputs[0], -0.5);
+
+            // w0 += 0.1 * (1.0 * -0.5), w1 += 0.1 * -1.0
+            let actual: Vec<_> = layer.current_weights().collect();
+
+            assert_relative_eq!(actual.as_slice(), [0.45, -0.6].as_slice());
+
+            layer.propagate_into(&[1.0, 2.0], &mut outputs);
+
+            assert_relative_eq!(outputs[0], 0.45 - 1.2);
+        }
+
+        #[test]
+        fn forgets_after_reset() {
+            let mut layer = layer();
+            let mut first = [0.0];
+            let mut second = [0.0];
+
+            layer.propagate_into(&[1.0, 2.0], &mut first);
+            layer.reset();
+            layer.propagate_into(&[1.0, 2.0], &mut second);
+
+            assert_relative_eq!(first[0], second[0]);
+        }
+
+        #[test]
+        fn clamps_weights() {
+            let mut layer = PlasticLayer::new(
+                vec![0.0],
+                vec![0.0],
+                vec![1.0],
+                vec![[0.0, 0.0, 0.0, 100.0]],
+                Activation::Identity,
+            );
+
+            layer.propagate_into(&[1.0], &mut [0.0]);
+
+            assert_eq!(
+                layer.current_weights().collect::<Vec<_>>(),
+                vec![PlasticLayer::WEIGHT_LIMIT]
+            );
+        }
+
+        #[test]
+        fn recovers_from_saturation() {
+            let mut layer = PlasticLayer::new(
+                vec![0.0],
+                vec![0.0],
+                vec![1.0],
+                vec![[0.0, 20.0, 0.0, 0.0]],
+                Activation::Identity,
+            );
+
+            layer.propagate_into(&[1.0], &mut [0.0]);
+            layer.propagate_into(&[1.0], &mut [0.0]);
+            layer.propagate_into(&[-0.5], &mut [0.0]);
+
+            // 10.0 - 0.5 * 20.0, rather than 40.0 - 10.0 clamped to 10.0
+            assert_relative_eq!(layer.current_weights().next().unwrap(), 0.0);
+        }
+    }
+
+    mod batch {
+        use super::*;
+
+        #[test]
+        fn keeps_weights_intact() {
+            let layer = layer();
+            let mut outputs = [0.0; 2];
+
+            layer.propagate_batch(&[1.0, 2.0, 1.0, 2.0], &mut outputs);
+
+            assert_relative_eq!(outputs.as_slice(), [-0.5, -0.5].as_slice());
+            assert!(layer.deltas.iter().all(|&delta| delta == 0.0));
+        }
+    }
+
+    mod weights {
+        use super::*;
+
+        #[test]
+        fn round_trips_through_from_weights() {
+            let weights: Vec<_> = (0..2 * (2 + 5 * 3)).map(|n| n as f32).collect();
+            let mut layer =
+                PlasticLayer::from_weights(3, 2, Activation::ReLU, &mut weights.iter().cloned());
+
+            assert_eq!(layer.weights().collect::<Vec<_>>(), weights);
+            assert_eq!(layer.weight_count(), weights.len());
+
+            let actual: Vec<_> = layer.weights_mut().map(|weight| *weight).collect();
+
+            assert_eq!(actual, weights);
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use super::gate::Gate;
+use crate::*;
+
+/// Elman-style recurrent layer.
+///
+/// Besides its inputs, every neuron sees the layer's outputs from the
+/// previous `propagate_into` call - its own one (self connection) and the
+/// other neurons' ones (lateral connections).
+#[derive(Clone, Debug)]
+pub struct RecurrentLayer {
+    gate: Gate,
+    activation: Activation,
+    state: Vec<f32>,
+}
+
+impl RecurrentLayer {
+    pub fn new(
+        biases: Vec<f32>,
+        input_weights: Vec<f32>,
+        recurrent_weights: Vec<f32>,
+        activation: Activation,
+    ) -> Self {
+        Self::from_gate(
+            Gate::new(biases, input_weights, recurrent_weights),
+            activation,
+        )
+    }
+
+    fn from_gate(gate: Gate, activation: Activation) -> Self {
+        Self {
+            state: vec![0.0; gate.output_size()],
+            gate,
+            activation,
+        }
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.gate.input_size()
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.gate.output_size()
+    }
+
+    pub fn activation(&self) -> Activation {
+        self.activation
+    }
+
+    /// Outputs from the previous `propagate_into` call.
+    pub fn state(&self) -> &[f32] {
+        &self.state
+    }
+
+    pub fn reset(&mut self) {
+        self.state.fill(0.0);
+    }
+
+    pub fn propagate_into(&mut self, inputs: &[f32], outputs: &mut [f32]) {
+        self.forward(inputs, outputs);
+        self.state.copy_from_slice(outputs);
+    }
+
+    /// Like `propagate_into`, but also stores the neurons' values before the
+    /// activation into `sums`.
+    pub fn propagate_traced(&mut self, inputs: &[f32], sums: &mut [f32], outputs: &mut [f32]) {
+        self.forward_train(inputs, sums, outputs);
+        self.state.copy_from_slice(outputs);
+    }
+
+    pub fn propagate_batch(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len() % self.input_size(), 0);
+        assert_eq!(
+            inputs.len() / self.input_size() * self.output_size(),
+            outputs.len()
+        );
+
+        for (inputs, outputs) in inputs
+            .chunks_exact(self.input_size())
+            .zip(outputs.chunks_exact_mut(self.output_size()))
+        {
+            self.forward(inputs, outputs);
+        }
+    }
+
+    fn forward(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.input_size());
+
+        for (neuron, output) in outputs.iter_mut().enumerate() {
+            *output = self.gate.sum(neuron, inputs, &self.state);
+        }
+
+        self.activation.apply_all(outputs);
+    }
+
+    /// Like `propagate_batch` for a single input, but also stores the
+    /// neurons' values before the activation into `sums`.
+    pub(crate) fn forward_train(&self, inputs: &[f32], sums: &mut [f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.input_size());
+
+        for (neuron, sum) in sums.iter_mut().enumerate() {
+            *sum = self.gate.sum(neuron, inputs, &self.state);
+        }
+
+        outputs.copy_from_slice(sums);
+        self.activation.apply_all(outputs);
+    }
+
+    /// See `DenseLayer::backward`; the state is treated as a constant input,
+    /// i.e. the gradient doesn't flow back through time.
+    pub(crate) fn backward(
+        &self,
+        inputs: &[f32],
+        sums: &[f32],
+        deltas: &mut [f32],
+        input_gradients: &mut [f32],
+        gradients: &mut [f32],
+    ) {
+        for (delta, sum) in deltas.iter_mut().zip(sums) {
+            *delta *= self.activation.derivative(*sum);
+        }
+
+        self.gate
+            .backward(inputs, &self.state, deltas, input_gradients, gradients);
+    }
+
+    pub(crate) fn weight_count(&self) -> usize {
+        self.gate.weight_count()
+    }
+
+    pub(crate) fn weights_mut(&mut self) -> impl Iterator<Item = &mut f32> + '_ {
+        self.gate.weights_mut()
+    }
+
+    /// Iterates over the neurons' parameters: bias, input weights and then
+    /// recurrent weights.
+    pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        self.gate.weights()
+    }
+
+    pub fn random(
+        rng: &mut dyn RngCore,
+        input_size: usize,
+        output_size: usize,
+        activation: Activation,
+    ) -> Self {
+        Self::from_weights(
+            input_size,
+            output_size,
+            activation,
+            &mut std::iter::repeat_with(|| rng.gen_range(-1.0..=1.0)),
+        )
+    }
+
+    pub fn from_weights(
+        input_size: usize,
+        output_size: usize,
+        activation: Activation,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Self {
+        Self::from_gate(
+            Gate::from_weights(input_size, output_size, weights),
+            activation,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn layer() -> RecurrentLayer {
+        RecurrentLayer::new(
+            vec![0.1, -0.1],
+            vec![0.5, -0.5, 0.25, 0.75],
+            vec![0.9, 0.0, -0.3, 0.2],
+            Activation::Tanh,
+        )
+    }
+
+    mod propagate_into {
+        use super::*;
+
+        #[test]
+        fn remembers_previous_outputs() {
+            let mut layer = layer();
+            let mut first = [0.0; 2];
+            let mut second = [0.0; 2];
+
+            layer.propagate_into(&[1.0, 0.5], &mut first);
+            layer.propagate_into(&[1.0, 0.5], &mut second);
+
+            let expected_first = [
+                (0.1f32 + 0.5 - 0.25).tanh(),
+                (-0.1f32 + 0.25 + 0.375).tanh(),
+            ];
+
+            let expected_second = [
+                (0.1 + 0.5 - 0.25 + 0.9 * expected_first[0]).tanh(),
+                (-0.1 + 0.25 + 0.375 - 0.3 * expected_first[0] + 0.2 * expected_first[1]).tanh(),
+            ];
+
+            assert_relative_eq!(first.as_slice(), expected_first.as_slice());
+            assert_relative_eq!(second.as_slice(), expected_second.as_slice());
+            assert_relative_eq!(layer.state(), expected_second.as_slice());
+        }
+
+        #[test]
+        fn forgets_after_reset() {
+            let mut layer = layer();
+            let mut first = [0.0; 2];
+            let mut second = [0.0; 2];
+
+            layer.propagate_into(&[1.0, 0.5], &mut first);
+            layer.reset();
+            layer.propagate_into(&[1.0, 0.5], &mut second);
+
+            assert_relative_eq!(first.as_slice(), second.as_slice());
+        }
+    }
+
+    mod propagate_batch {
+        use super::*;
+
+        #[test]
+        fn keeps_state_intact() {
+            let mut layer = layer();
+            let mut outputs = [0.0; 2];
+            layer.propagate_into(&[1.0, 0.5], &mut outputs);
+
+            let mut batch = [0.0; 4];
+            layer.propagate_batch(&[0.0, 1.0, 1.0, 0.0], &mut batch);
+
+            assert_relative_eq!(layer.state(), outputs.as_slice());
+
+            let mut expected = [0.0; 2];
+            layer.clone().propagate_into(&[1.0, 0.0], &mut expected);
+
+            assert_relative_eq!(&batch[2..], expected.as_slice());
+        }
+    }
+
+    mod weights {
+        use super::*;
+
+        #[test]
+        fn round_trips_through_from_weights() {
+            let layer = layer();
+            let weights: Vec<_> = layer.weights().collect();
+
+            assert_eq!(
+                weights,
+                vec![0.1, 0.5, -0.5, 0.9, 0.0, -0.1, 0.25, 0.75, -0.3, 0.2]
+            );
+
+            let restored =
+                RecurrentLayer::from_weights(2, 2, Activation::Tanh, &mut weights.into_iter());
+
+            assert_eq!(
+                restored.weights().collect::<Vec<_>>(),
+                layer.weights().collect::<Vec<_>>()
+            );
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::*;
+
+/// Parameterless layer that turns its inputs into probabilities summing up
+/// to one.
+///
+/// Softmax replaces the activation, so it always reports `Identity`.
+#[derive(Clone, Debug)]
+pub struct SoftmaxLayer {
+    size: usize,
+}
+
+impl SoftmaxLayer {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0);
+
+        Self { size }
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.size
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.size
+    }
+
+    pub fn activation(&self) -> Activation {
+        Activation::Identity
+    }
+
+    pub fn propagate_into(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.size);
+
+        // Shifting by the maximum keeps `exp` from overflowing
+        let max = inputs.iter().fold(f32::NEG_INFINITY, |max, &x| max.max(x));
+
+        for (output, input) in outputs.iter_mut().zip(inputs) {
+            *output = libm::expf(input - max);
+        }
+
+        let sum: f32 = outputs.iter().sum();
+
+        for output in outputs {
+            *output /= sum;
+        }
+    }
+
+    /// Like `propagate_into`, but also stores the inputs (logits) into
+    /// `sums`.
+    pub fn propagate_traced(&self, inputs: &[f32], sums: &mut [f32], outputs: &mut [f32]) {
+        sums.copy_from_slice(inputs);
+        self.propagate_into(inputs, outputs);
+    }
+
+    /// See `DenseLayer::backward`; there are no parameters, so only the
+    /// inputs' gradient is computed.
+    pub(crate) fn backward(&self, sums: &[f32], deltas: &[f32], input_gradients: &mut [f32]) {
+        let mut outputs = vec![0.0; self.size];
+
+        self.propagate_into(sums, &mut outputs);
+
+        // dy_i/dx_j = y_i * (1[i = j] - y_j)
+        let dot = math::dot(deltas, &outputs);
+
+        for ((input_gradient, delta), output) in
+            input_gradients.iter_mut().zip(deltas).zip(&outputs)
+        {
+            *input_gradient = output * (delta - dot);
+        }
+    }
+
+    pub fn propagate_batch(&self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), outputs.len());
+
+        for (inputs, outputs) in inputs
+            .chunks_exact(self.size)
+            .zip(outputs.chunks_exact_mut(self.size))
+        {
+            self.propagate_into(inputs, outputs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn propagate() {
+        let mut outputs = [0.0; 3];
+
+        SoftmaxLayer::new(3).propagate_into(&[1.0, 2.0, 3.0], &mut outputs);
+
+        let sum = 1.0f32.exp() + 2.0f32.exp() + 3.0f32.exp();
+
+        let expected = [1.0f32.exp() / sum, 2.0f32.exp() / sum, 3.0f32.exp() / sum];
+
+        assert_relative_eq!(outputs.as_slice(), expected.as_slice());
+
+        SoftmaxLayer::new(3).propagate_into(&[1000.0, 1000.0, 0.0], &mut outputs);
+
+        assert_relative_eq!(outputs.as_slice(), [0.5, 0.5, 0.0].as_slice());
+    }
+
+    #[test]
+    fn backward() {
+        let layer = SoftmaxLayer::new(3);
+        let (inputs, deltas) = ([0.5, -1.0, 2.0], [1.0, 0.0, -2.0]);
+        let mut input_gradients = [0.0; 3];
+
+        layer.backward(&inputs, &deltas, &mut input_gradients);
+
+        // loss = dot(deltas, softmax(inputs)), differentiated numerically
+        let loss = |inputs: &[f32]| {
+            let mut outputs = [0.0; 3];
+            layer.propagate_into(inputs, &mut outputs);
+            math::dot(&deltas, &outputs)
+        };
+
+        for input in 0..3 {
+            let (mut plus, mut minus) = (inputs, inputs);
+
+            plus[input] += 1e-2;
+            minus[input] -= 1e-2;
+
+            assert_relative_eq!(
+                input_gradients[input],
+                (loss(&plus) - loss(&minus)) / 2e-2,
+                epsilon = 1e-3
+            );
+        }
+    }
+}
@@ -1,3 +1,157 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{activation::Activation, initializer::Initializer};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LayerTopology {
     pub neurons: usize,
+    /// Ignored for the input layer.
+    #[serde(default)]
+    pub activation: Activation,
+    /// Ignored for the input layer.
+    #[serde(default)]
+    pub kind: LayerKind,
+    /// Ignored for the input layer.
+    #[serde(default)]
+    pub initializer: Initializer,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LayerKind {
+    /// Fully-connected, feed-forward layer.
+    #[default]
+    Dense,
+    /// Elman-style layer, whose neurons additionally see the layer's own
+    /// outputs from the previous `propagate` call (through self- and lateral
+    /// connections).
+    Recurrent,
+    /// Long short-term memory layer, whose `activation` is used for the
+    /// candidate and the cell's output (the gates use sigmoid).
+    Lstm,
+    /// Gated recurrent unit layer, whose `activation` is used for the
+    /// candidate (the gates use sigmoid).
+    Gru,
+    /// Fully-connected layer whose weights change during propagation,
+    /// following evolved Hebbian rules.
+    Plastic,
+    /// Turns the inputs into probabilities; requires as many neurons as
+    /// there are inputs and ignores `activation`.
+    Softmax,
+    /// Normalizes the inputs (see `LayerNormLayer`); requires as many
+    /// neurons as there are inputs.
+    LayerNorm,
+    /// One-dimensional convolution with an odd-sized `kernel`; neurons have
+    /// to be a multiple of the inputs - one input-sized row per channel.
+    Conv1d { kernel: usize },
+}
+
+impl LayerKind {
+    /// Whether `Network::gradients` (and so training) and
+    /// `Network::jacobian` work through layers of this kind.
+    pub(crate) fn supports_backpropagation(self) -> bool {
+        matches!(
+            self,
+            Self::Dense | Self::Recurrent | Self::Softmax | Self::LayerNorm
+        )
+    }
+
+    /// Number of weight matrices (each with its own biases) of the layer.
+    pub(crate) fn gates(self) -> usize {
+        match self {
+            Self::Lstm => 4,
+            Self::Gru => 3,
+            _ => 1,
+        }
+    }
+
+    /// Names of the gates, in the order their weights are laid out; empty
+    /// for layers with a single weight matrix.
+    pub(crate) fn gate_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Lstm => &["input", "forget", "candidate", "output"],
+            Self::Gru => &["update", "reset", "candidate"],
+            _ => &[],
+        }
+    }
+}
+
+impl LayerTopology {
+    pub fn new(neurons: usize) -> Self {
+        Self {
+            neurons,
+            activation: Activation::default(),
+            kind: LayerKind::default(),
+            initializer: Initializer::default(),
+        }
+    }
+
+    pub fn with_activation(mut self, activation: Activation) -> Self {
+        self.activation = activation;
+        self
+    }
+
+    pub fn with_kind(mut self, kind: LayerKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_initializer(mut self, initializer: Initializer) -> Self {
+        self.initializer = initializer;
+        self
+    }
+
+    /// Number of weights per neuron (of each gate, or of each channel for
+    /// convolutions), excluding the bias.
+    pub(crate) fn fan_in(&self, input_size: usize) -> usize {
+        match self.kind {
+            LayerKind::Dense | LayerKind::Plastic => input_size,
+            LayerKind::Recurrent | LayerKind::Lstm | LayerKind::Gru => input_size + self.neurons,
+            LayerKind::Softmax => 0,
+            LayerKind::LayerNorm => 1,
+            LayerKind::Conv1d { kernel } => kernel,
+        }
+    }
+
+    /// Layer's parameters (except for the plastic layers' rules) come in
+    /// blocks of a bias followed by `fan_in` weights; returns the number of
+    /// such blocks.
+    pub(crate) fn blocks(&self, input_size: usize) -> usize {
+        match self.kind {
+            LayerKind::Softmax => 0,
+            LayerKind::Conv1d { .. } => self.neurons / input_size,
+            kind => kind.gates() * self.neurons,
+        }
+    }
+
+    /// Number of parameters of a layer with given number of inputs; `None`
+    /// when it doesn't fit in `usize`.
+    pub(crate) fn weight_count(&self, input_size: usize) -> Option<usize> {
+        // Same as `fan_in` and `blocks`, but checked - the topology might've
+        // come from an untrusted file
+        let fan_in = match self.kind {
+            LayerKind::Recurrent | LayerKind::Lstm | LayerKind::Gru => {
+                input_size.checked_add(self.neurons)?
+            }
+            _ => self.fan_in(input_size),
+        };
+
+        let blocks = match self.kind {
+            LayerKind::Softmax | LayerKind::Conv1d { .. } => self.blocks(input_size),
+            kind => kind.gates().checked_mul(self.neurons)?,
+        };
+
+        let weights = blocks.checked_mul(fan_in.checked_add(1)?)?;
+
+        if self.kind == LayerKind::Plastic {
+            // Learning rate plus four coefficients per connection
+            let rules = input_size
+                .checked_mul(4)
+                .and_then(|coeffs| coeffs.checked_add(1))
+                .and_then(|rules| rules.checked_mul(self.neurons))?;
+
+            weights.checked_add(rules)
+        } else {
+            Some(weights)
+        }
+    }
 }
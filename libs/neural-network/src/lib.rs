@@ -1,56 +1,260 @@
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
+pub use self::{
+    ctrnn::*, diff::*, error::*, model::*, quantized::*, scratch::*, sensitivity::*, trace::*,
+};
+
+use activation::Activation;
 use layer::*;
-use layer_topology::LayerTopology;
+use layer_topology::{LayerKind, LayerTopology};
 use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
 
+pub mod activation;
+mod ctrnn;
+mod diff;
+mod error;
+pub mod graph;
+pub mod initializer;
 mod layer;
 pub mod layer_topology;
-mod neuron;
+mod math;
+mod model;
+pub mod neat;
+#[cfg(feature = "onnx")]
+mod onnx;
+mod prune;
+mod quantized;
+mod scratch;
+mod sensitivity;
+mod trace;
+pub mod train;
 
+/// Serializes as a model file - see the `model` module.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(into = "model::Model", try_from = "model::Model")]
 pub struct Network {
     layers: Vec<Layer>,
 }
 
 impl Network {
     pub fn new(layers: Vec<Layer>) -> Self {
-        Self { layers }
+        Self::try_new(layers).unwrap_or_else(|err| panic!("{err}"))
     }
 
+    /// Layers are numbered as in the topology, i.e. the first one is #1.
+    pub fn try_new(layers: Vec<Layer>) -> Result<Self, Error> {
+        if layers.is_empty() {
+            return Err(Error::NotEnoughLayers(0));
+        }
+
+        for (idx, pair) in layers.windows(2).enumerate() {
+            if pair[0].output_size() != pair[1].input_size() {
+                return Err(Error::LayerSizeMismatch {
+                    layer: idx + 2,
+                    expected: pair[0].output_size(),
+                    actual: pair[1].input_size(),
+                });
+            }
+        }
+
+        Ok(Self { layers })
+    }
+
+    /// Draws from `rng` in a fixed order (layer by layer, neuron by neuron,
+    /// bias first), so the same seed always yields the same network.
+    ///
+    /// See `LayerTopology::with_initializer`.
     pub fn random(rng: &mut dyn RngCore, layers: &[LayerTopology]) -> Self {
-        assert!(layers.len() > 1);
+        Self::try_random(rng, layers).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_random(rng: &mut dyn RngCore, layers: &[LayerTopology]) -> Result<Self, Error> {
+        Self::validate_topology(layers)?;
 
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::random(rng, layers[0].neurons, layers[1].neurons))
+            .map(|layers| Layer::random(rng, layers[0].neurons, &layers[1]))
             .collect();
 
-        Self { layers }
+        Ok(Self { layers })
     }
 
-    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+    /// Checks whether `layers` form a valid network (the same way
+    /// `try_random` and `try_from_weights` do), without building it.
+    pub fn validate_topology(layers: &[LayerTopology]) -> Result<(), Error> {
+        if layers.len() < 2 {
+            return Err(Error::NotEnoughLayers(layers.len()));
+        }
+
+        if let Some(layer) = layers.iter().position(|layer| layer.neurons == 0) {
+            return Err(Error::EmptyLayer { layer });
+        }
+
+        for (idx, pair) in layers.windows(2).enumerate() {
+            let (inputs, neurons) = (pair[0].neurons, pair[1].neurons);
+
+            let reason = match pair[1].kind {
+                LayerKind::Softmax | LayerKind::LayerNorm if neurons != inputs => {
+                    "expected as many neurons as there are inputs"
+                }
+                LayerKind::Conv1d { .. } if neurons % inputs != 0 => {
+                    "expected a multiple of the inputs as the number of neurons"
+                }
+                LayerKind::Conv1d { kernel } if kernel % 2 == 0 => "expected an odd kernel",
+                _ => continue,
+            };
+
+            return Err(Error::InvalidLayer {
+                layer: idx + 1,
+                reason,
+            });
+        }
+
+        Self::checked_weight_count(layers)?;
+
+        Ok(())
+    }
+
+    /// Number of weights (biases included) a network of given topology has.
+    ///
+    /// Panics when that number doesn't fit in `usize`, which
+    /// `validate_topology` reports as `Error::TooManyWeights`.
+    pub fn weight_count(layers: &[LayerTopology]) -> usize {
+        Self::checked_weight_count(layers).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn checked_weight_count(layers: &[LayerTopology]) -> Result<usize, Error> {
+        layers
+            .windows(2)
+            .enumerate()
+            .try_fold(0usize, |count, (idx, layers)| {
+                layers[1]
+                    .weight_count(layers[0].neurons)
+                    .and_then(|weights| count.checked_add(weights))
+                    .ok_or(Error::TooManyWeights { layer: idx + 1 })
+            })
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.layers[0].input_size()
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.layers[self.layers.len() - 1].output_size()
+    }
+
+    /// Recurrent layers remember their outputs between the calls, until
+    /// `reset`.
+    pub fn propagate(&mut self, inputs: Vec<f32>) -> Vec<f32> {
         self.layers
-            .iter()
-            .fold(inputs, |inputs, layer| layer.propagate(inputs))
+            .iter_mut()
+            .fold(inputs, |inputs, layer| layer.propagate(&inputs))
+    }
+
+    pub fn try_propagate(&mut self, inputs: Vec<f32>) -> Result<Vec<f32>, Error> {
+        self.check_input_size(inputs.len())?;
+
+        Ok(self.propagate(inputs))
+    }
+
+    fn check_input_size(&self, actual: usize) -> Result<(), Error> {
+        let expected = self.input_size();
+
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(Error::InputSizeMismatch { expected, actual })
+        }
+    }
+
+    /// Like `propagate`, but performs no allocations once `scratch` has grown
+    /// to fit this network (which `NetworkScratch::new` does upfront).
+    pub fn propagate_into<'a>(
+        &mut self,
+        inputs: &[f32],
+        scratch: &'a mut NetworkScratch,
+    ) -> &'a [f32] {
+        scratch.propagate(&mut self.layers, inputs)
+    }
+
+    pub fn try_propagate_into<'a>(
+        &mut self,
+        inputs: &[f32],
+        scratch: &'a mut NetworkScratch,
+    ) -> Result<&'a [f32], Error> {
+        self.check_input_size(inputs.len())?;
+
+        Ok(self.propagate_into(inputs, scratch))
+    }
+
+    /// Propagates many input vectors at once; `inputs` holds them one after
+    /// another and so does the returned vector.
+    ///
+    /// Each input is propagated independently, from the network's current
+    /// state - which is left unchanged.
+    pub fn propagate_batch(&self, inputs: &[f32]) -> Vec<f32> {
+        let batch_size = inputs.len() / self.input_size();
+
+        self.layers.iter().fold(inputs.to_vec(), |inputs, layer| {
+            let mut outputs = vec![0.0; batch_size * layer.output_size()];
+            layer.propagate_batch(&inputs, &mut outputs);
+            outputs
+        })
+    }
+
+    /// Forgets everything the recurrent layers remember.
+    pub fn reset(&mut self) {
+        for layer in &mut self.layers {
+            layer.reset();
+        }
+    }
+
+    /// Topology this network could've been created from, input layer
+    /// included.
+    pub fn topology(&self) -> Vec<LayerTopology> {
+        std::iter::once(LayerTopology::new(self.input_size()))
+            .chain(self.layers.iter().map(Layer::topology))
+            .collect()
     }
 
     pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
-        use std::iter::once;
+        self.layers.iter().flat_map(Layer::weights)
+    }
 
-        self.layers
-            .iter()
-            .flat_map(|layer| layer.neurons.iter())
-            .flat_map(|neuron| once(&neuron.bias).chain(&neuron.weights))
-            .cloned()
+    /// Iterates over the weights in the same order as `weights`.
+    pub(crate) fn weights_mut(&mut self) -> impl Iterator<Item = &mut f32> + '_ {
+        self.layers.iter_mut().flat_map(Layer::weights_mut)
     }
 
     pub fn from_weights(layers: &[LayerTopology], weights: impl IntoIterator<Item = f32>) -> Self {
+        Self::try_from_weights(layers, weights).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_from_weights(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = f32>,
+    ) -> Result<Self, Error> {
+        Self::validate_topology(layers)?;
+
+        let weights: Vec<_> = weights.into_iter().collect();
+        let expected = Self::weight_count(layers);
+
+        if weights.len() != expected {
+            return Err(Error::WeightCountMismatch {
+                expected,
+                actual: weights.len(),
+            });
+        }
+
         let mut weights = weights.into_iter();
 
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::from_weights(layers[0].neurons, layers[1].neurons, &mut weights))
+            .map(|layers| Layer::from_weights(layers[0].neurons, &layers[1], &mut weights))
             .collect();
 
-        Self { layers }
+        Ok(Self { layers })
     }
 }
 
@@ -58,10 +262,17 @@ impl Network {
 mod tests {
     use super::*;
 
-    mod random {
+    fn dense(layer: &Layer) -> &DenseLayer {
+        match layer {
+            Layer::Dense(layer) => layer,
+            _ => panic!("expected a dense layer"),
+        }
+    }
 
+    mod random {
         use super::*;
         use approx::assert_relative_eq;
+        use initializer::Initializer;
         use rand::SeedableRng;
         use rand_chacha::ChaCha8Rng;
 
@@ -72,60 +283,416 @@ mod tests {
             let network = Network::random(
                 &mut rng,
                 &[
-                    LayerTopology { neurons: 3 },
-                    LayerTopology { neurons: 2 },
-                    LayerTopology { neurons: 1 },
+                    LayerTopology::new(3),
+                    LayerTopology::new(2),
+                    LayerTopology::new(1).with_activation(Activation::Tanh),
                 ],
             );
 
             assert_eq!(network.layers.len(), 2);
-            assert_eq!(network.layers[0].neurons.len(), 2);
+            assert_eq!(network.layers[0].output_size(), 2);
 
-            assert_relative_eq!(network.layers[0].neurons[0].bias, -0.6255188);
+            assert_relative_eq!(dense(&network.layers[0]).bias(0), -0.6255188);
             assert_relative_eq!(
-                network.layers[0].neurons[0].weights.as_slice(),
+                dense(&network.layers[0]).neuron_weights(0),
                 &[0.67383957, 0.8181262, 0.26284897].as_slice()
             );
 
-            assert_relative_eq!(network.layers[0].neurons[1].bias, 0.5238807);
+            assert_relative_eq!(dense(&network.layers[0]).bias(1), 0.5238807);
             assert_relative_eq!(
-                network.layers[0].neurons[0].weights.as_slice(),
+                dense(&network.layers[0]).neuron_weights(0),
                 &[0.67383957, 0.8181262, 0.26284897].as_slice()
             );
 
-            assert_eq!(network.layers[1].neurons.len(), 1);
+            assert_eq!(network.layers[0].activation(), Activation::ReLU);
+            assert_eq!(network.layers[1].activation(), Activation::Tanh);
+
+            assert_eq!(network.layers[1].output_size(), 1);
             assert_relative_eq!(
-                network.layers[1].neurons[0].weights.as_slice(),
+                dense(&network.layers[1]).neuron_weights(0),
                 &[-0.48879617, -0.19277132].as_slice()
             );
         }
+
+        #[test]
+        fn initializers() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(2).with_initializer(Initializer::Constant(0.5)),
+                    LayerTopology::new(1)
+                        .with_kind(LayerKind::Recurrent)
+                        .with_initializer(Initializer::Zeros),
+                ],
+            );
+
+            let actual: Vec<_> = network.weights().collect();
+
+            assert_eq!(
+                actual,
+                vec![0.0, 0.5, 0.5, 0.0, 0.5, 0.5, 0.0, 0.0, 0.0, 0.0]
+            );
+        }
+
+        #[test]
+        fn gated_is_deterministic() {
+            let layers = &[
+                LayerTopology::new(3),
+                LayerTopology::new(4).with_kind(LayerKind::Lstm),
+                LayerTopology::new(2).with_kind(LayerKind::Gru),
+            ];
+
+            let network = |seed| {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                Network::random(&mut rng, layers)
+                    .weights()
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(network(0), network(0));
+            assert_ne!(network(0), network(1));
+        }
+
+        #[test]
+        fn rejects_invalid_topologies() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            assert_eq!(
+                Network::try_random(&mut rng, &[LayerTopology::new(3)]).unwrap_err(),
+                Error::NotEnoughLayers(1)
+            );
+
+            assert_eq!(
+                Network::try_random(
+                    &mut rng,
+                    &[
+                        LayerTopology::new(3),
+                        LayerTopology::new(0),
+                        LayerTopology::new(1)
+                    ]
+                )
+                .unwrap_err(),
+                Error::EmptyLayer { layer: 1 }
+            );
+
+            assert_eq!(
+                Network::try_random(
+                    &mut rng,
+                    &[
+                        LayerTopology::new(3),
+                        LayerTopology::new(4).with_kind(LayerKind::Softmax)
+                    ]
+                )
+                .unwrap_err(),
+                Error::InvalidLayer {
+                    layer: 1,
+                    reason: "expected as many neurons as there are inputs"
+                }
+            );
+
+            assert!(matches!(
+                Network::try_random(
+                    &mut rng,
+                    &[
+                        LayerTopology::new(3),
+                        LayerTopology::new(6).with_kind(LayerKind::Conv1d { kernel: 2 })
+                    ]
+                ),
+                Err(Error::InvalidLayer { layer: 1, .. })
+            ));
+        }
+
+        #[test]
+        fn convolutional() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let mut network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(5),
+                    LayerTopology::new(10)
+                        .with_kind(LayerKind::Conv1d { kernel: 3 })
+                        .with_initializer(Initializer::HeUniform),
+                    LayerTopology::new(10).with_kind(LayerKind::LayerNorm),
+                    LayerTopology::new(2).with_activation(Activation::Identity),
+                    LayerTopology::new(2).with_kind(LayerKind::Softmax),
+                ],
+            );
+
+            // Conv: 2 channels x (1 + 3), layer norm: 10 x 2, dense: 2 x (1 + 10)
+            assert_eq!(network.weights().count(), 8 + 20 + 22);
+
+            let outputs = network.propagate(vec![0.1, 0.5, 1.0, 0.5, 0.1]);
+
+            assert_relative_eq!(outputs.iter().sum::<f32>(), 1.0);
+        }
+    }
+
+    mod try_new {
+        use super::*;
+
+        #[test]
+        fn rejects_mismatched_layers() {
+            let layer = || {
+                Layer::Dense(DenseLayer::new(
+                    vec![0.0; 2],
+                    vec![0.0; 6],
+                    Activation::ReLU,
+                ))
+            };
+
+            assert!(Network::try_new(vec![layer()]).is_ok());
+
+            assert_eq!(
+                Network::try_new(vec![layer(), layer()]).unwrap_err(),
+                Error::LayerSizeMismatch {
+                    layer: 2,
+                    expected: 2,
+                    actual: 3
+                }
+            );
+
+            assert_eq!(
+                Network::try_new(Vec::new()).unwrap_err(),
+                Error::NotEnoughLayers(0)
+            );
+        }
     }
 
     mod propagate {
         use approx::assert_relative_eq;
 
-        use self::neuron::Neuron;
+        use super::*;
+
+        fn layers() -> [Layer; 2] {
+            [
+                Layer::Dense(DenseLayer::new(
+                    vec![0.0, 0.0],
+                    vec![-0.5, -0.4, -0.3, -0.2, -0.1, 0.0],
+                    Activation::LeakyReLU(0.01),
+                )),
+                Layer::Recurrent(RecurrentLayer::new(
+                    vec![0.0],
+                    vec![-0.5, 0.5],
+                    vec![0.5],
+                    Activation::Tanh,
+                )),
+            ]
+        }
+
+        #[test]
+        fn test() {
+            let mut layers = layers();
+            let mut network = Network::new(layers.to_vec());
+
+            for inputs in [vec![0.5, 0.6, 0.7], vec![0.5, 0.6, 0.7], vec![0.0; 3]] {
+                let actual = network.propagate(inputs.clone());
+                let hidden = layers[0].propagate(&inputs);
+                let expected = layers[1].propagate(&hidden);
+
+                assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            }
+        }
+
+        #[test]
+        fn reset() {
+            let mut network = Network::new(layers().to_vec());
+
+            let first = network.propagate(vec![0.5, 0.6, 0.7]);
+            let second = network.propagate(vec![0.5, 0.6, 0.7]);
+
+            network.reset();
+
+            let third = network.propagate(vec![0.5, 0.6, 0.7]);
+
+            assert_ne!(first, second);
+            assert_eq!(first, third);
+        }
+
+        #[test]
+        fn into_scratch() {
+            let mut network = Network::new(layers().to_vec());
+            let mut reference = Network::new(layers().to_vec());
+            let mut scratch = NetworkScratch::new(&[
+                LayerTopology::new(3),
+                LayerTopology::new(2),
+                LayerTopology::new(1),
+            ]);
+
+            for inputs in [[0.5, 0.6, 0.7], [-1.0, 0.0, 1.0]] {
+                let expected = reference.propagate(inputs.to_vec());
+                let actual = network.propagate_into(&inputs, &mut scratch);
+
+                assert_relative_eq!(actual, expected.as_slice());
+            }
+        }
+
+        #[test]
+        fn into_undersized_scratch() {
+            let mut network = Network::new(layers().to_vec());
+            let mut scratch = NetworkScratch::default();
+
+            let expected = network.clone().propagate(vec![0.5, 0.6, 0.7]);
+            let actual = network.propagate_into(&[0.5, 0.6, 0.7], &mut scratch);
+
+            assert_relative_eq!(actual, expected.as_slice());
+        }
+
+        #[test]
+        fn batch() {
+            let mut network = Network::new(layers().to_vec());
+            network.propagate(vec![1.0, 1.0, 1.0]);
+
+            let inputs = [[0.5, 0.6, 0.7], [-1.0, 0.0, 1.0], [0.1, 0.2, 0.3]];
+
+            let actual = network.propagate_batch(inputs.as_flattened());
+            let expected: Vec<_> = inputs
+                .iter()
+                .flat_map(|inputs| network.clone().propagate(inputs.to_vec()))
+                .collect();
+
+            assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn rejects_wrong_input_sizes() {
+            let mut network = Network::new(layers().to_vec());
 
+            assert_eq!(
+                network.try_propagate(vec![0.5, 0.6]).unwrap_err(),
+                Error::InputSizeMismatch {
+                    expected: 3,
+                    actual: 2
+                }
+            );
+
+            assert!(network.try_propagate(vec![0.5, 0.6, 0.7]).is_ok());
+        }
+    }
+
+    mod from_weights {
         use super::*;
+        use approx::assert_relative_eq;
 
         #[test]
         fn test() {
-            let layers = vec![
-                Layer::new(vec![
-                    Neuron::new(0.0, vec![-0.5, -0.4, -0.3]),
-                    Neuron::new(0.0, vec![-0.2, -0.1, 0.0]),
-                ]),
-                Layer::new(vec![Neuron::new(0.0, vec![-0.5, 0.5])]),
+            let layers = &[
+                LayerTopology::new(3),
+                LayerTopology::new(2).with_activation(Activation::Sigmoid),
+                LayerTopology::new(1).with_activation(Activation::Identity),
             ];
 
-            let network = Network::new(vec![layers[0].clone(), layers[1].clone()]);
+            let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 1.1];
 
-            let inputs = vec![0.5, 0.6, 0.7];
+            let network = Network::from_weights(layers, weights.clone());
+            let actual: Vec<_> = network.weights().collect();
 
-            let actual = network.propagate(inputs.clone());
-            let expected = layers[1].propagate(layers[0].propagate(inputs.clone()));
+            assert_relative_eq!(actual.as_slice(), weights.as_slice());
+            assert_eq!(network.layers[0].activation(), Activation::Sigmoid);
+            assert_eq!(network.layers[1].activation(), Activation::Identity);
+        }
 
-            assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        #[test]
+        fn recurrent() {
+            let layers = &[
+                LayerTopology::new(2),
+                LayerTopology::new(2).with_kind(LayerKind::Recurrent),
+                LayerTopology::new(1),
+            ];
+
+            let weights: Vec<_> = (1..=13).map(|n| n as f32 / 10.0).collect();
+
+            let network = Network::from_weights(layers, weights.clone());
+            let actual: Vec<_> = network.weights().collect();
+
+            assert!(matches!(network.layers[0], Layer::Recurrent(_)));
+            assert_relative_eq!(actual.as_slice(), weights.as_slice());
+        }
+
+        #[test]
+        fn gated() {
+            let layers = &[
+                LayerTopology::new(2),
+                LayerTopology::new(2).with_kind(LayerKind::Lstm),
+                LayerTopology::new(1).with_kind(LayerKind::Gru),
+            ];
+
+            // LSTM: 4 gates x 2 neurons x (1 + 2 + 2), GRU: 3 gates x 1 neuron
+            // x (1 + 2 + 1)
+            let weights: Vec<_> = (0..40 + 12).map(|n| n as f32 / 100.0).collect();
+
+            let network = Network::from_weights(layers, weights.clone());
+            let actual: Vec<_> = network.weights().collect();
+
+            assert!(matches!(network.layers[0], Layer::Lstm(_)));
+            assert!(matches!(network.layers[1], Layer::Gru(_)));
+            assert_relative_eq!(actual.as_slice(), weights.as_slice());
+        }
+
+        #[test]
+        fn parameterless() {
+            let layers = &[
+                LayerTopology::new(2),
+                LayerTopology::new(2).with_kind(LayerKind::Softmax),
+            ];
+
+            let mut network = Network::from_weights(layers, Vec::new());
+
+            assert_eq!(network.topology()[1].kind, LayerKind::Softmax);
+
+            let outputs = network.propagate(vec![1.0, 1.0]);
+
+            assert_relative_eq!(outputs.as_slice(), [0.5, 0.5].as_slice());
+        }
+
+        #[test]
+        fn plastic() {
+            let layers = &[
+                LayerTopology::new(2),
+                LayerTopology::new(3).with_kind(LayerKind::Plastic),
+                LayerTopology::new(1),
+            ];
+
+            // Plastic: 3 neurons x (1 + 2) weights, plus 3 neurons x (1 + 4 x 2)
+            // coefficients
+            assert_eq!(Network::weight_count(layers), 9 + 27 + 4);
+
+            let weights: Vec<_> = (0..40).map(|n| n as f32 / 100.0).collect();
+
+            let network = Network::from_weights(layers, weights.clone());
+            let actual: Vec<_> = network.weights().collect();
+
+            assert!(matches!(network.layers[0], Layer::Plastic(_)));
+            assert_relative_eq!(actual.as_slice(), weights.as_slice());
+        }
+
+        #[test]
+        fn rejects_wrong_weight_counts() {
+            let layers = &[LayerTopology::new(3), LayerTopology::new(1)];
+
+            assert_eq!(Network::weight_count(layers), 4);
+
+            for actual in [3, 5] {
+                assert_eq!(
+                    Network::try_from_weights(layers, vec![0.0; actual]).unwrap_err(),
+                    Error::WeightCountMismatch {
+                        expected: 4,
+                        actual
+                    }
+                );
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "got too many weights")]
+        fn panics_on_leftover_weights() {
+            Network::from_weights(
+                &[LayerTopology::new(1), LayerTopology::new(1)],
+                vec![0.0; 3],
+            );
         }
     }
 }
@@ -0,0 +1,56 @@
+//! Kernels shared by the layers; with the `simd` feature enabled they're
+//! vectorized using portable SIMD.
+//!
+//! Note that the vectorized dot product sums in a different order, so its
+//! results may differ from the scalar one in the last bits - seeded runs are
+//! reproducible only between builds with the same set of features.
+
+#[cfg(feature = "simd")]
+pub(crate) const LANES: usize = 8;
+
+#[cfg(not(feature = "simd"))]
+pub(crate) fn dot(a: &[f32], b: &[f32]) -> f32 {
+    debug_assert_eq!(a.len(), b.len());
+
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+#[cfg(feature = "simd")]
+pub(crate) fn dot(a: &[f32], b: &[f32]) -> f32 {
+    use std::simd::{num::SimdFloat, Simd};
+
+    debug_assert_eq!(a.len(), b.len());
+
+    let (a_chunks, a_rest) = a.as_chunks::<LANES>();
+    let (b_chunks, b_rest) = b.as_chunks::<LANES>();
+
+    let sum = a_chunks
+        .iter()
+        .zip(b_chunks)
+        .fold(Simd::splat(0.0), |sum, (a, b)| {
+            sum + Simd::from_array(*a) * Simd::from_array(*b)
+        });
+
+    sum.reduce_sum() + a_rest.iter().zip(b_rest).map(|(a, b)| a * b).sum::<f32>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn dot_of_short_vectors() {
+        assert_relative_eq!(dot(&[1.0, 2.0, 3.0], &[4.0, -5.0, 6.0]), 12.0);
+    }
+
+    #[test]
+    fn dot_of_long_vectors() {
+        let a: Vec<_> = (0..19).map(|n| n as f32).collect();
+        let b: Vec<_> = (0..19).map(|n| 1.0 - n as f32 / 10.0).collect();
+
+        let expected: f32 = (0..19).map(|n| n as f32 * (1.0 - n as f32 / 10.0)).sum();
+
+        assert_relative_eq!(dot(&a, &b), expected, epsilon = 1e-4);
+    }
+}
@@ -0,0 +1,339 @@
+//! Versioned model files - a network's topology and weights, stored either
+//! as JSON or in a compact binary form.
+//!
+//! Only the parameters are stored; recurrent layers' state is not.
+
+use std::{error, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// Version of the model files written by this crate.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Header of binary model files, followed by the format version (as a
+/// little-endian `u32`) and then the bincode-encoded model.
+const MAGIC: &[u8; 4] = b"SLNN";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct Model {
+    format_version: u32,
+    topology: Vec<LayerTopology>,
+    weights: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct ModelVersion {
+    format_version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BinaryModel {
+    topology: Vec<LayerTopology>,
+    weights: Vec<f32>,
+}
+
+#[derive(Debug)]
+pub enum ModelError {
+    Json(serde_json::Error),
+    Binary(bincode::Error),
+    /// The binary file doesn't start with the expected header.
+    NotAModel,
+    UnsupportedVersion(u32),
+    /// The topology and weights don't form a valid network.
+    Network(Error),
+    NonFiniteWeight {
+        index: usize,
+    },
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "couldn't parse model: {err}"),
+            Self::Binary(err) => write!(f, "couldn't decode model: {err}"),
+            Self::NotAModel => write!(f, "not a model file"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported model format version: {version} (expected {FORMAT_VERSION})"
+            ),
+            Self::Network(err) => write!(f, "invalid model: {err}"),
+            Self::NonFiniteWeight { index } => write!(f, "weight #{index} is not finite"),
+        }
+    }
+}
+
+impl error::Error for ModelError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Json(err) => Some(err),
+            Self::Binary(err) => Some(err),
+            Self::Network(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Model {
+    fn into_network(self) -> Result<Network, ModelError> {
+        if self.format_version != FORMAT_VERSION {
+            return Err(ModelError::UnsupportedVersion(self.format_version));
+        }
+
+        if let Some(index) = self.weights.iter().position(|weight| !weight.is_finite()) {
+            return Err(ModelError::NonFiniteWeight { index });
+        }
+
+        Network::try_from_weights(&self.topology, self.weights).map_err(ModelError::Network)
+    }
+}
+
+impl From<Network> for Model {
+    fn from(network: Network) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            topology: network.topology(),
+            weights: network.weights().collect(),
+        }
+    }
+}
+
+impl TryFrom<Model> for Network {
+    type Error = ModelError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        model.into_network()
+    }
+}
+
+impl Network {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&Model::from(self.clone())).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ModelError> {
+        let version: ModelVersion = serde_json::from_str(json).map_err(ModelError::Json)?;
+
+        if version.format_version != FORMAT_VERSION {
+            return Err(ModelError::UnsupportedVersion(version.format_version));
+        }
+
+        serde_json::from_str::<Model>(json)
+            .map_err(ModelError::Json)?
+            .into_network()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let model = BinaryModel {
+            topology: self.topology(),
+            weights: self.weights().collect(),
+        };
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend(bincode::serialize(&model).unwrap());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ModelError> {
+        let bytes = bytes.strip_prefix(MAGIC).ok_or(ModelError::NotAModel)?;
+
+        let (version, bytes) = bytes
+            .split_first_chunk::<4>()
+            .ok_or(ModelError::NotAModel)?;
+
+        let format_version = u32::from_le_bytes(*version);
+
+        if format_version != FORMAT_VERSION {
+            return Err(ModelError::UnsupportedVersion(format_version));
+        }
+
+        let model: BinaryModel = bincode::deserialize(bytes).map_err(ModelError::Binary)?;
+
+        Model {
+            format_version,
+            topology: model.topology,
+            weights: model.weights,
+        }
+        .into_network()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use layer_topology::LayerKind;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn network() -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(3),
+                LayerTopology::new(4)
+                    .with_kind(LayerKind::Recurrent)
+                    .with_activation(Activation::LeakyReLU(0.01)),
+                LayerTopology::new(2).with_activation(Activation::Tanh),
+            ],
+        )
+    }
+
+    fn assert_same(actual: &Network, expected: &Network) {
+        let actual_weights: Vec<_> = actual.weights().collect();
+        let expected_weights: Vec<_> = expected.weights().collect();
+
+        assert_eq!(actual.topology(), expected.topology());
+        assert_relative_eq!(actual_weights.as_slice(), expected_weights.as_slice());
+    }
+
+    mod json {
+        use super::*;
+
+        #[test]
+        fn round_trips() {
+            let network = network();
+            let restored = Network::from_json(&network.to_json()).unwrap();
+
+            assert_same(&restored, &network);
+        }
+
+        #[test]
+        fn works_through_serde() {
+            let network = network();
+            let json = serde_json::to_string(&network).unwrap();
+            let restored: Network = serde_json::from_str(&json).unwrap();
+
+            assert_same(&restored, &network);
+        }
+
+        #[test]
+        fn rejects_other_versions() {
+            let json = r#"{ "format_version": 2, "layers": [] }"#;
+
+            assert!(matches!(
+                Network::from_json(json),
+                Err(ModelError::UnsupportedVersion(2))
+            ));
+        }
+
+        #[test]
+        fn rejects_mismatched_weights() {
+            let json = r#"{
+                "format_version": 1,
+                "topology": [{ "neurons": 2 }, { "neurons": 1, "activation": "Tanh" }],
+                "weights": [0.1, 0.2]
+            }"#;
+
+            assert!(matches!(
+                Network::from_json(json),
+                Err(ModelError::Network(Error::WeightCountMismatch {
+                    expected: 3,
+                    actual: 2
+                }))
+            ));
+        }
+
+        #[test]
+        fn rejects_invalid_topologies() {
+            let json = r#"{
+                "format_version": 1,
+                "topology": [{ "neurons": 2 }],
+                "weights": []
+            }"#;
+
+            assert!(matches!(
+                Network::from_json(json),
+                Err(ModelError::Network(Error::NotEnoughLayers(1)))
+            ));
+        }
+
+        #[test]
+        fn rejects_too_many_weights() {
+            let json = r#"{
+                "format_version": 1,
+                "topology": [
+                    { "neurons": 4294967296 },
+                    { "neurons": 4294967296, "kind": "Recurrent" }
+                ],
+                "weights": []
+            }"#;
+
+            assert!(matches!(
+                Network::from_json(json),
+                Err(ModelError::Network(Error::TooManyWeights { layer: 1 }))
+            ));
+        }
+    }
+
+    mod bytes {
+        use super::*;
+
+        #[test]
+        fn round_trips() {
+            let network = network();
+            let restored = Network::from_bytes(&network.to_bytes()).unwrap();
+
+            assert_same(&restored, &network);
+        }
+
+        #[test]
+        fn rejects_other_files() {
+            assert!(matches!(
+                Network::from_bytes(b"{}"),
+                Err(ModelError::NotAModel)
+            ));
+
+            let mut bytes = network().to_bytes();
+            bytes[4] = 9;
+
+            assert!(matches!(
+                Network::from_bytes(&bytes),
+                Err(ModelError::UnsupportedVersion(9))
+            ));
+
+            let bytes = network().to_bytes();
+
+            assert!(matches!(
+                Network::from_bytes(&bytes[..bytes.len() - 1]),
+                Err(ModelError::Binary(_))
+            ));
+        }
+
+        #[test]
+        fn rejects_non_finite_weights() {
+            let mut bytes = network().to_bytes();
+            let len = bytes.len();
+            bytes[len - 4..].copy_from_slice(&f32::NAN.to_le_bytes());
+
+            assert!(matches!(
+                Network::from_bytes(&bytes),
+                Err(ModelError::NonFiniteWeight { index: 41 })
+            ));
+        }
+
+        #[test]
+        fn rejects_too_many_weights() {
+            let model = BinaryModel {
+                topology: vec![
+                    LayerTopology::new(1 << 32),
+                    LayerTopology::new(1 << 32).with_kind(LayerKind::Recurrent),
+                ],
+                weights: Vec::new(),
+            };
+
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+            bytes.extend(bincode::serialize(&model).unwrap());
+
+            assert!(matches!(
+                Network::from_bytes(&bytes),
+                Err(ModelError::Network(Error::TooManyWeights { layer: 1 }))
+            ));
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! NeuroEvolution of Augmenting Topologies - evolving networks' structure
+//! together with their weights.
+//!
+//! A `Genome` starts minimal (inputs connected straight to outputs) and grows
+//! through `NeatMutation`, which adds nodes and connections; `NeatCrossover`
+//! then aligns parents' genes by their innovation numbers, which are handed
+//! out by `Innovations` shared by the entire population.
+//!
+//! `Genome::network` builds the phenotype - a `NeatNetwork`.
+
+pub use self::{crossover::*, genome::*, innovations::*, mutation::*, network::*};
+
+use crate::*;
+
+mod crossover;
+mod genome;
+mod innovations;
+mod mutation;
+mod network;
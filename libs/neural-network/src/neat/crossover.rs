@@ -0,0 +1,160 @@
+use crate::neat::*;
+
+pub struct NeatCrossover {
+    /// Chance of disabling a gene that's disabled in either of the parents
+    disable_chance: f32,
+}
+
+impl NeatCrossover {
+    pub fn new(disable_chance: f32) -> Self {
+        assert!((0.0..=1.0).contains(&disable_chance));
+
+        Self { disable_chance }
+    }
+
+    /// Aligns parents' genes by their innovation numbers (and node ids):
+    /// matching genes are inherited from a random parent, while disjoint and
+    /// excess ones are inherited from the fitter parent only.
+    ///
+    /// For equally fit parents, pass them in a random order.
+    pub fn crossover(&self, rng: &mut dyn RngCore, fitter: &Genome, other: &Genome) -> Genome {
+        let nodes = fitter
+            .nodes()
+            .iter()
+            .map(|node| match other.node(node.id) {
+                Some(other) if rng.gen_bool(0.5) => *other,
+                _ => *node,
+            })
+            .collect();
+
+        let connections = fitter
+            .connections()
+            .iter()
+            .map(|connection| {
+                let Some(other) = other.connection(connection.innovation) else {
+                    return *connection;
+                };
+
+                let mut child = if rng.gen_bool(0.5) {
+                    *other
+                } else {
+                    *connection
+                };
+
+                if !connection.enabled || !other.enabled {
+                    child.enabled = !rng.gen_bool(self.disable_chance as _);
+                }
+
+                child
+            })
+            .collect();
+
+        Genome { nodes, connections }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn node(id: usize, kind: NodeKind, bias: f32) -> NodeGene {
+        NodeGene {
+            id,
+            kind,
+            bias,
+            activation: Activation::Identity,
+        }
+    }
+
+    fn connection(innovation: usize, from: usize, to: usize, weight: f32) -> ConnectionGene {
+        ConnectionGene {
+            innovation,
+            from,
+            to,
+            weight,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn aligns_genes_by_innovation() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let fitter = Genome::new(
+            vec![
+                node(0, NodeKind::Input, 0.0),
+                node(1, NodeKind::Output, 1.0),
+                node(2, NodeKind::Hidden, 1.0),
+            ],
+            vec![
+                connection(0, 0, 1, 1.0),
+                connection(1, 0, 2, 1.0),
+                connection(2, 2, 1, 1.0),
+            ],
+        );
+
+        let other = Genome::new(
+            vec![
+                node(0, NodeKind::Input, 0.0),
+                node(1, NodeKind::Output, -1.0),
+                node(3, NodeKind::Hidden, -1.0),
+            ],
+            vec![
+                connection(0, 0, 1, -1.0),
+                connection(3, 0, 3, -1.0),
+                connection(4, 3, 1, -1.0),
+            ],
+        );
+
+        let mut from_other = 0;
+
+        for _ in 0..100 {
+            let child = NeatCrossover::new(0.75).crossover(&mut rng, &fitter, &other);
+
+            let ids: Vec<_> = child.nodes().iter().map(|node| node.id).collect();
+
+            let innovations: Vec<_> = child
+                .connections()
+                .iter()
+                .map(|connection| connection.innovation)
+                .collect();
+
+            assert_eq!(ids, vec![0, 1, 2]);
+            assert_eq!(innovations, vec![0, 1, 2]);
+            assert_eq!(child.connections()[1].weight, 1.0);
+            assert_eq!(child.connections()[2].weight, 1.0);
+
+            if child.connections()[0].weight == -1.0 {
+                from_other += 1;
+            }
+        }
+
+        assert!((30..70).contains(&from_other));
+    }
+
+    #[test]
+    fn mostly_disables_genes_disabled_in_either_parent() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let nodes = vec![
+            node(0, NodeKind::Input, 0.0),
+            node(1, NodeKind::Output, 0.0),
+        ];
+        let fitter = Genome::new(nodes.clone(), vec![connection(0, 0, 1, 1.0)]);
+        let mut other = fitter.clone();
+        other.connections[0].enabled = false;
+
+        let disabled = (0..1000)
+            .filter(|_| {
+                !NeatCrossover::new(0.75)
+                    .crossover(&mut rng, &fitter, &other)
+                    .connections()[0]
+                    .enabled
+            })
+            .count();
+
+        assert!((700..800).contains(&disabled));
+    }
+}
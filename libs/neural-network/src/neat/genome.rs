@@ -0,0 +1,206 @@
+use crate::neat::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    Input,
+    Output,
+    Hidden,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NodeGene {
+    pub id: usize,
+    pub kind: NodeKind,
+    /// Ignored for input nodes.
+    pub bias: f32,
+    /// Ignored for input nodes.
+    pub activation: Activation,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConnectionGene {
+    pub innovation: usize,
+    pub from: usize,
+    pub to: usize,
+    pub weight: f32,
+    pub enabled: bool,
+}
+
+/// Node genes (ordered by their ids) and connection genes (ordered by their
+/// innovation numbers).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Genome {
+    pub(crate) nodes: Vec<NodeGene>,
+    pub(crate) connections: Vec<ConnectionGene>,
+}
+
+impl Genome {
+    pub fn new(mut nodes: Vec<NodeGene>, mut connections: Vec<ConnectionGene>) -> Self {
+        nodes.sort_by_key(|node| node.id);
+        connections.sort_by_key(|connection| connection.innovation);
+
+        assert!(nodes.windows(2).all(|nodes| nodes[0].id != nodes[1].id));
+
+        assert!(connections
+            .windows(2)
+            .all(|connections| connections[0].innovation != connections[1].innovation));
+
+        let genome = Self { nodes, connections };
+
+        for connection in &genome.connections {
+            assert!(genome.node(connection.from).is_some());
+
+            assert!(genome
+                .node(connection.to)
+                .is_some_and(|node| node.kind != NodeKind::Input));
+        }
+
+        genome
+    }
+
+    /// Creates a genome without any hidden nodes, with every input connected
+    /// to every output; draws the weights from `rng`, input by input.
+    pub fn minimal(
+        rng: &mut dyn RngCore,
+        innovations: &mut Innovations,
+        activation: Activation,
+    ) -> Self {
+        let inputs = innovations.inputs();
+        let outputs = innovations.outputs();
+
+        let nodes = (0..inputs + outputs)
+            .map(|id| NodeGene {
+                id,
+                kind: if id < inputs {
+                    NodeKind::Input
+                } else {
+                    NodeKind::Output
+                },
+                bias: 0.0,
+                activation,
+            })
+            .collect();
+
+        let mut connections = Vec::with_capacity(inputs * outputs);
+
+        for from in 0..inputs {
+            for to in inputs..inputs + outputs {
+                connections.push(ConnectionGene {
+                    innovation: innovations.connection(from, to),
+                    from,
+                    to,
+                    weight: rng.gen_range(-1.0..=1.0),
+                    enabled: true,
+                });
+            }
+        }
+
+        Self::new(nodes, connections)
+    }
+
+    pub fn nodes(&self) -> &[NodeGene] {
+        &self.nodes
+    }
+
+    pub fn connections(&self) -> &[ConnectionGene] {
+        &self.connections
+    }
+
+    pub fn node(&self, id: usize) -> Option<&NodeGene> {
+        self.position(id).map(|idx| &self.nodes[idx])
+    }
+
+    pub fn connection(&self, innovation: usize) -> Option<&ConnectionGene> {
+        self.connections
+            .binary_search_by_key(&innovation, |connection| connection.innovation)
+            .ok()
+            .map(|idx| &self.connections[idx])
+    }
+
+    pub fn network(&self) -> NeatNetwork {
+        NeatNetwork::new(self)
+    }
+
+    pub(crate) fn position(&self, id: usize) -> Option<usize> {
+        self.nodes.binary_search_by_key(&id, |node| node.id).ok()
+    }
+
+    pub(crate) fn is_connected(&self, from: usize, to: usize) -> bool {
+        self.connections
+            .iter()
+            .any(|connection| connection.from == from && connection.to == to)
+    }
+
+    pub(crate) fn add_node(&mut self, node: NodeGene) {
+        let idx = self.nodes.partition_point(|other| other.id < node.id);
+        self.nodes.insert(idx, node);
+    }
+
+    pub(crate) fn add_connection(&mut self, connection: ConnectionGene) {
+        let idx = self
+            .connections
+            .partition_point(|other| other.innovation < connection.innovation);
+
+        self.connections.insert(idx, connection);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    mod minimal {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut innovations = Innovations::new(2, 1);
+
+            let genome = Genome::minimal(&mut rng, &mut innovations, Activation::Tanh);
+
+            let kinds: Vec<_> = genome.nodes().iter().map(|node| node.kind).collect();
+
+            let connections: Vec<_> = genome
+                .connections()
+                .iter()
+                .map(|connection| (connection.innovation, connection.from, connection.to))
+                .collect();
+
+            assert_eq!(
+                kinds,
+                vec![NodeKind::Input, NodeKind::Input, NodeKind::Output]
+            );
+
+            assert_eq!(connections, vec![(0, 0, 2), (1, 1, 2)]);
+        }
+    }
+
+    mod new {
+        use super::*;
+
+        #[test]
+        #[should_panic]
+        fn rejects_connections_into_inputs() {
+            let node = |id, kind| NodeGene {
+                id,
+                kind,
+                bias: 0.0,
+                activation: Activation::Identity,
+            };
+
+            Genome::new(
+                vec![node(0, NodeKind::Input), node(1, NodeKind::Output)],
+                vec![ConnectionGene {
+                    innovation: 0,
+                    from: 1,
+                    to: 0,
+                    weight: 1.0,
+                    enabled: true,
+                }],
+            );
+        }
+    }
+}
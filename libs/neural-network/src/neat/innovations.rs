@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+/// Hands out node ids and innovation numbers, making sure the same
+/// structural mutation gets the same numbers in every genome.
+///
+/// Input nodes get ids `0..inputs`, output nodes the ones right after them.
+#[derive(Clone, Debug)]
+pub struct Innovations {
+    inputs: usize,
+    outputs: usize,
+    next_node: usize,
+    next_innovation: usize,
+    connections: HashMap<(usize, usize), usize>,
+    splits: HashMap<usize, usize>,
+}
+
+impl Innovations {
+    pub fn new(inputs: usize, outputs: usize) -> Self {
+        assert!(inputs > 0);
+        assert!(outputs > 0);
+
+        Self {
+            inputs,
+            outputs,
+            next_node: inputs + outputs,
+            next_innovation: 0,
+            connections: HashMap::new(),
+            splits: HashMap::new(),
+        }
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.outputs
+    }
+
+    /// Innovation number of the connection between given nodes.
+    pub fn connection(&mut self, from: usize, to: usize) -> usize {
+        *self.connections.entry((from, to)).or_insert_with(|| {
+            self.next_innovation += 1;
+            self.next_innovation - 1
+        })
+    }
+
+    /// Id of the node that splits given connection.
+    pub fn split(&mut self, innovation: usize) -> usize {
+        *self.splits.entry(innovation).or_insert_with(|| {
+            self.next_node += 1;
+            self.next_node - 1
+        })
+    }
+
+    /// Id of a brand new node.
+    pub fn node(&mut self) -> usize {
+        self.next_node += 1;
+        self.next_node - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_numbers_of_repeated_mutations() {
+        let mut innovations = Innovations::new(2, 1);
+
+        assert_eq!(innovations.connection(0, 2), 0);
+        assert_eq!(innovations.connection(1, 2), 1);
+        assert_eq!(innovations.connection(0, 2), 0);
+
+        assert_eq!(innovations.split(1), 3);
+        assert_eq!(innovations.split(0), 4);
+        assert_eq!(innovations.split(1), 3);
+        assert_eq!(innovations.node(), 5);
+    }
+}
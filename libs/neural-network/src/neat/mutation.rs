@@ -0,0 +1,331 @@
+use rand::seq::SliceRandom;
+
+use crate::neat::*;
+
+pub struct NeatMutation {
+    /// Chance of perturbing each of the weights and biases
+    weight_chance: f32,
+    /// Magnitude of that perturbation
+    weight_coefficient: f32,
+    add_connection_chance: f32,
+    add_node_chance: f32,
+    toggle_chance: f32,
+    allow_recurrent: bool,
+    hidden_activation: Activation,
+}
+
+impl NeatMutation {
+    pub fn new(weight_chance: f32, weight_coefficient: f32) -> Self {
+        assert!((0.0..=1.0).contains(&weight_chance));
+
+        Self {
+            weight_chance,
+            weight_coefficient,
+            add_connection_chance: 0.05,
+            add_node_chance: 0.03,
+            toggle_chance: 0.01,
+            allow_recurrent: false,
+            hidden_activation: Activation::default(),
+        }
+    }
+
+    pub fn with_add_connection_chance(mut self, chance: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+
+        self.add_connection_chance = chance;
+        self
+    }
+
+    pub fn with_add_node_chance(mut self, chance: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+
+        self.add_node_chance = chance;
+        self
+    }
+
+    pub fn with_toggle_chance(mut self, chance: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+
+        self.toggle_chance = chance;
+        self
+    }
+
+    /// Allows adding connections that close cycles; by default genomes stay
+    /// acyclic.
+    pub fn with_recurrent(mut self, allow_recurrent: bool) -> Self {
+        self.allow_recurrent = allow_recurrent;
+        self
+    }
+
+    /// Activation of the nodes created by this mutation.
+    pub fn with_hidden_activation(mut self, activation: Activation) -> Self {
+        self.hidden_activation = activation;
+        self
+    }
+
+    /// Perturbs the weights and then - each with its own chance - adds a
+    /// connection, adds a node and toggles a connection.
+    pub fn mutate(
+        &self,
+        rng: &mut dyn RngCore,
+        genome: &mut Genome,
+        innovations: &mut Innovations,
+    ) {
+        self.mutate_weights(rng, genome);
+
+        if rng.gen_bool(self.add_connection_chance as _) {
+            self.add_connection(rng, genome, innovations);
+        }
+
+        if rng.gen_bool(self.add_node_chance as _) {
+            self.add_node(rng, genome, innovations);
+        }
+
+        if rng.gen_bool(self.toggle_chance as _) {
+            Self::toggle_connection(rng, genome);
+        }
+    }
+
+    fn mutate_weights(&self, rng: &mut dyn RngCore, genome: &mut Genome) {
+        let biases = genome
+            .nodes
+            .iter_mut()
+            .filter(|node| node.kind != NodeKind::Input)
+            .map(|node| &mut node.bias);
+
+        let weights = genome
+            .connections
+            .iter_mut()
+            .map(|connection| &mut connection.weight);
+
+        for gene in biases.chain(weights) {
+            let sign = if rng.gen_bool(0.5) { -1.0 } else { 1.0 };
+
+            if rng.gen_bool(self.weight_chance as _) {
+                *gene += sign * self.weight_coefficient * rng.gen::<f32>();
+            }
+        }
+    }
+
+    /// Connects two so far unconnected nodes; does nothing if there are no
+    /// such nodes.
+    pub fn add_connection(
+        &self,
+        rng: &mut dyn RngCore,
+        genome: &mut Genome,
+        innovations: &mut Innovations,
+    ) {
+        let candidates: Vec<_> = genome
+            .nodes
+            .iter()
+            .flat_map(|from| genome.nodes.iter().map(move |to| (from, to)))
+            .filter(|(_, to)| to.kind != NodeKind::Input)
+            .map(|(from, to)| (from.id, to.id))
+            .filter(|&(from, to)| !genome.is_connected(from, to))
+            .filter(|&(from, to)| self.allow_recurrent || !Self::reaches(genome, to, from))
+            .collect();
+
+        let Some(&(from, to)) = candidates.choose(rng) else {
+            return;
+        };
+
+        genome.add_connection(ConnectionGene {
+            innovation: innovations.connection(from, to),
+            from,
+            to,
+            weight: rng.gen_range(-1.0..=1.0),
+            enabled: true,
+        });
+    }
+
+    /// Splits an enabled connection in two, putting a new node in between;
+    /// the incoming connection gets weight of 1.0 and the outgoing one gets
+    /// the original weight, so the network's behavior changes as little as
+    /// possible.
+    pub fn add_node(
+        &self,
+        rng: &mut dyn RngCore,
+        genome: &mut Genome,
+        innovations: &mut Innovations,
+    ) {
+        let candidates: Vec<_> = genome
+            .connections
+            .iter()
+            .enumerate()
+            .filter(|(_, connection)| connection.enabled)
+            .map(|(idx, _)| idx)
+            .collect();
+
+        let Some(&idx) = candidates.choose(rng) else {
+            return;
+        };
+
+        let split = &mut genome.connections[idx];
+        split.enabled = false;
+
+        let split = *split;
+        let mut node = innovations.split(split.innovation);
+
+        // The same connection got split before (and re-enabled since)
+        if genome.node(node).is_some() {
+            node = innovations.node();
+        }
+
+        genome.add_node(NodeGene {
+            id: node,
+            kind: NodeKind::Hidden,
+            bias: 0.0,
+            activation: self.hidden_activation,
+        });
+
+        genome.add_connection(ConnectionGene {
+            innovation: innovations.connection(split.from, node),
+            from: split.from,
+            to: node,
+            weight: 1.0,
+            enabled: true,
+        });
+
+        genome.add_connection(ConnectionGene {
+            innovation: innovations.connection(node, split.to),
+            from: node,
+            to: split.to,
+            weight: split.weight,
+            enabled: true,
+        });
+    }
+
+    pub fn toggle_connection(rng: &mut dyn RngCore, genome: &mut Genome) {
+        if let Some(connection) = genome.connections.choose_mut(rng) {
+            connection.enabled = !connection.enabled;
+        }
+    }
+
+    /// Whether there's a path from `from` to `to`, including through the
+    /// disabled connections (which might get re-enabled later).
+    fn reaches(genome: &Genome, from: usize, to: usize) -> bool {
+        let mut visited = vec![false; genome.nodes.len()];
+        let mut pending = vec![from];
+
+        while let Some(node) = pending.pop() {
+            if node == to {
+                return true;
+            }
+
+            let idx = genome.position(node).unwrap();
+
+            if std::mem::replace(&mut visited[idx], true) {
+                continue;
+            }
+
+            pending.extend(
+                genome
+                    .connections
+                    .iter()
+                    .filter(|connection| connection.from == node)
+                    .map(|connection| connection.to),
+            );
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn genome(rng: &mut dyn RngCore, innovations: &mut Innovations) -> Genome {
+        Genome::minimal(rng, innovations, Activation::Identity)
+    }
+
+    mod add_node {
+        use super::*;
+
+        #[test]
+        fn keeps_the_behavior() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut innovations = Innovations::new(2, 1);
+            let mut genome = genome(&mut rng, &mut innovations);
+
+            let expected = genome.network().propagate(&[0.5, -1.0]);
+
+            NeatMutation::new(0.0, 0.0)
+                .with_hidden_activation(Activation::Identity)
+                .add_node(&mut rng, &mut genome, &mut innovations);
+
+            let actual = genome.network().propagate(&[0.5, -1.0]);
+
+            assert_eq!(genome.nodes().len(), 4);
+            assert_eq!(genome.nodes()[3].kind, NodeKind::Hidden);
+            assert_eq!(genome.connections().len(), 4);
+            assert_eq!(
+                genome
+                    .connections()
+                    .iter()
+                    .filter(|connection| !connection.enabled)
+                    .count(),
+                1
+            );
+            assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn shares_innovations_between_genomes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut innovations = Innovations::new(1, 1);
+            let mutation = NeatMutation::new(0.0, 0.0);
+
+            let mut a = genome(&mut rng, &mut innovations);
+            let mut b = genome(&mut rng, &mut innovations);
+
+            mutation.add_node(&mut rng, &mut a, &mut innovations);
+            mutation.add_node(&mut rng, &mut b, &mut innovations);
+
+            let structure = |genome: &Genome| {
+                genome
+                    .connections()
+                    .iter()
+                    .map(|connection| (connection.innovation, connection.from, connection.to))
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(structure(&a), vec![(0, 0, 1), (1, 0, 2), (2, 2, 1)]);
+            assert_eq!(structure(&a), structure(&b));
+        }
+    }
+
+    mod add_connection {
+        use super::*;
+
+        #[test]
+        fn keeps_genomes_acyclic() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut innovations = Innovations::new(1, 1);
+            let mut genome = genome(&mut rng, &mut innovations);
+            let mutation = NeatMutation::new(0.0, 0.0);
+
+            mutation.add_node(&mut rng, &mut genome, &mut innovations);
+
+            // The only remaining acyclic connections are 0 -> 1 (which
+            // exists, but is disabled) and so there's nothing to add
+            for _ in 0..10 {
+                mutation.add_connection(&mut rng, &mut genome, &mut innovations);
+            }
+
+            assert_eq!(genome.connections().len(), 3);
+
+            let mutation = mutation.with_recurrent(true);
+
+            for _ in 0..10 {
+                mutation.add_connection(&mut rng, &mut genome, &mut innovations);
+            }
+
+            // 1 -> 1, 1 -> 2, 2 -> 2
+            assert_eq!(genome.connections().len(), 6);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use crate::neat::*;
+
+/// Network built from a `Genome`.
+///
+/// Neurons are evaluated in dependency order, so acyclic genomes behave like
+/// regular feed-forward networks; connections that close a cycle carry the
+/// value from the previous `propagate` call instead (until `reset`).
+///
+/// Nodes that don't lead to any output are skipped.
+#[derive(Clone, Debug)]
+pub struct NeatNetwork {
+    inputs: Vec<usize>,
+    outputs: Vec<usize>,
+    neurons: Vec<NeatNeuron>,
+    values: Vec<f32>,
+}
+
+#[derive(Clone, Debug)]
+struct NeatNeuron {
+    value: usize,
+    bias: f32,
+    activation: Activation,
+    connections: Vec<(usize, f32)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    Pending,
+    InProgress,
+    Done,
+}
+
+impl NeatNetwork {
+    pub fn new(genome: &Genome) -> Self {
+        let nodes = genome.nodes();
+
+        let find = |kind| {
+            nodes
+                .iter()
+                .enumerate()
+                .filter(|(_, node)| node.kind == kind)
+                .map(|(idx, _)| idx)
+                .collect::<Vec<_>>()
+        };
+
+        let inputs = find(NodeKind::Input);
+        let outputs = find(NodeKind::Output);
+
+        let mut incoming = vec![Vec::new(); nodes.len()];
+
+        for connection in genome.connections() {
+            if connection.enabled {
+                let from = genome.position(connection.from).unwrap();
+                let to = genome.position(connection.to).unwrap();
+
+                incoming[to].push((from, connection.weight));
+            }
+        }
+
+        let mut visits = vec![Visit::Pending; nodes.len()];
+        let mut order = Vec::new();
+
+        for &output in &outputs {
+            Self::visit(nodes, &incoming, &mut visits, &mut order, output);
+        }
+
+        let neurons = order
+            .into_iter()
+            .map(|value| NeatNeuron {
+                value,
+                bias: nodes[value].bias,
+                activation: nodes[value].activation,
+                connections: std::mem::take(&mut incoming[value]),
+            })
+            .collect();
+
+        Self {
+            inputs,
+            outputs,
+            neurons,
+            values: vec![0.0; nodes.len()],
+        }
+    }
+
+    /// Depth-first search over the incoming connections, yielding the nodes
+    /// in post-order - i.e. each node after all the nodes it depends on.
+    fn visit(
+        nodes: &[NodeGene],
+        incoming: &[Vec<(usize, f32)>],
+        visits: &mut [Visit],
+        order: &mut Vec<usize>,
+        node: usize,
+    ) {
+        if visits[node] != Visit::Pending || nodes[node].kind == NodeKind::Input {
+            return;
+        }
+
+        visits[node] = Visit::InProgress;
+
+        for &(from, _) in &incoming[node] {
+            Self::visit(nodes, incoming, visits, order, from);
+        }
+
+        visits[node] = Visit::Done;
+        order.push(node);
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn propagate(&mut self, inputs: &[f32]) -> Vec<f32> {
+        let mut outputs = vec![0.0; self.output_size()];
+        self.propagate_into(inputs, &mut outputs);
+        outputs
+    }
+
+    pub fn propagate_into(&mut self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.input_size());
+        assert_eq!(outputs.len(), self.output_size());
+
+        for (&idx, &input) in self.inputs.iter().zip(inputs) {
+            self.values[idx] = input;
+        }
+
+        for neuron in &self.neurons {
+            let sum = neuron
+                .connections
+                .iter()
+                .map(|&(from, weight)| weight * self.values[from])
+                .sum::<f32>();
+
+            self.values[neuron.value] = neuron.activation.apply(neuron.bias + sum);
+        }
+
+        for (output, &idx) in outputs.iter_mut().zip(&self.outputs) {
+            *output = self.values[idx];
+        }
+    }
+
+    /// Forgets the values carried through the cycles.
+    pub fn reset(&mut self) {
+        self.values.fill(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn node(id: usize, kind: NodeKind, bias: f32) -> NodeGene {
+        NodeGene {
+            id,
+            kind,
+            bias,
+            activation: Activation::Identity,
+        }
+    }
+
+    fn connection(innovation: usize, from: usize, to: usize, weight: f32) -> ConnectionGene {
+        ConnectionGene {
+            innovation,
+            from,
+            to,
+            weight,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn propagates_through_acyclic_genomes() {
+        // 0 -> 3 -> 2, plus a skip connection 1 -> 2; the hidden node is
+        // listed after the output it feeds
+        let mut genome = Genome::new(
+            vec![
+                node(0, NodeKind::Input, 0.0),
+                node(1, NodeKind::Input, 0.0),
+                node(2, NodeKind::Output, 0.5),
+                node(3, NodeKind::Hidden, 1.0),
+            ],
+            vec![
+                connection(0, 0, 3, 2.0),
+                connection(1, 3, 2, 3.0),
+                connection(2, 1, 2, -1.0),
+            ],
+        );
+
+        let actual = genome.network().propagate(&[1.0, 4.0]);
+
+        assert_relative_eq!(actual[0], 0.5 + 3.0 * (1.0 + 2.0) - 4.0);
+
+        genome.connections[1].enabled = false;
+
+        let actual = genome.network().propagate(&[1.0, 4.0]);
+
+        assert_relative_eq!(actual[0], 0.5 - 4.0);
+    }
+
+    #[test]
+    fn delays_values_through_cycles() {
+        // 0 -> 1, with 1 feeding itself
+        let genome = Genome::new(
+            vec![
+                node(0, NodeKind::Input, 0.0),
+                node(1, NodeKind::Output, 0.0),
+            ],
+            vec![connection(0, 0, 1, 1.0), connection(1, 1, 1, 0.5)],
+        );
+
+        let mut network = genome.network();
+
+        assert_relative_eq!(network.propagate(&[1.0])[0], 1.0);
+        assert_relative_eq!(network.propagate(&[1.0])[0], 1.5);
+        assert_relative_eq!(network.propagate(&[0.0])[0], 0.75);
+
+        network.reset();
+
+        assert_relative_eq!(network.propagate(&[1.0])[0], 1.0);
+    }
+}
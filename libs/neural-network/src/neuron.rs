@@ -1,82 +0,0 @@
-use crate::*;
-
-#[derive(Clone)]
-pub struct Neuron {
-    pub bias: f32,
-    pub weights: Vec<f32>,
-}
-
-impl Neuron {
-    pub fn new(bias: f32, weights: Vec<f32>) -> Self {
-        Self { bias, weights }
-    }
-
-    pub fn propagate(&self, inputs: &[f32]) -> f32 {
-        assert_eq!(self.weights.len(), inputs.len());
-
-        let output = inputs
-            .iter()
-            .zip(&self.weights)
-            .map(|(input, weight)| input * weight)
-            .sum::<f32>();
-
-        (self.bias + output).max(0.0)
-    }
-
-    pub fn random(rng: &mut dyn rand::RngCore, input_size: usize) -> Self {
-        let bias = rng.gen_range(-1.0..=1.0);
-
-        let weights = (0..input_size).map(|_| rng.gen_range(-1.0..=1.0)).collect();
-
-        Self { bias, weights }
-    }
-
-    pub fn from_weights(input_size: usize, weights: &mut dyn Iterator<Item = f32>) -> Self {
-        let bias = weights.next().expect("got not enough weights");
-
-        let weights = (0..input_size)
-            .map(|_| weights.next().expect("got not enough weights"))
-            .collect();
-
-        Self { bias, weights }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    mod random {
-        use super::*;
-        use rand::SeedableRng;
-        use rand_chacha::ChaCha8Rng;
-
-        #[test]
-        fn test() {
-            let mut rng = ChaCha8Rng::from_seed(Default::default());
-            let neuron = Neuron::random(&mut rng, 4);
-
-            approx::assert_relative_eq!(neuron.bias, -0.6255188);
-            approx::assert_relative_eq!(
-                neuron.weights.as_slice(),
-                [0.67383957, 0.8181262, 0.26284897, 0.5238807].as_slice()
-            );
-        }
-    }
-
-    mod propagate {
-        use super::*;
-
-        #[test]
-        fn returns_propagated_input() {
-            let neuron = Neuron::new(0.5, vec![-0.3, 0.8]);
-
-            approx::assert_relative_eq!(neuron.propagate(&[-10.0, -10.0]), 0.0);
-
-            approx::assert_relative_eq!(
-                neuron.propagate(&[0.5, 1.0]),
-                (-0.3 * 0.5) + (0.8 * 1.0) + 0.5
-            );
-        }
-    }
-}
@@ -0,0 +1,880 @@
+//! ONNX export (and import of what's been exported) of feed-forward
+//! networks, i.e. ones made of dense and softmax layers.
+//!
+//! Each dense layer becomes a `Gemm` followed by its activation; those
+//! without a standard ONNX operator are composed of simpler ones (`Step` is
+//! `Cast(Greater(x, 0))` and `Gaussian` is `Exp(Neg(Mul(x, x)))`).
+//!
+//! The graph takes a single `input` of shape `[N, input_size]` and returns
+//! a single `output` of shape `[N, output_size]`.
+
+use std::collections::HashMap;
+use std::{error, fmt};
+
+use prost::Message;
+
+use crate::*;
+
+const IR_VERSION: i64 = 8;
+const OPSET_VERSION: i64 = 13;
+
+const INPUT: &str = "input";
+const OUTPUT: &str = "output";
+
+#[derive(Debug)]
+pub enum OnnxError {
+    Decode(prost::DecodeError),
+    /// The graph uses an operator (or a sequence of operators) that doesn't
+    /// map onto any of the layers or activations.
+    UnsupportedOperator(String),
+    /// The graph refers to a tensor it doesn't contain or whose shape doesn't
+    /// fit the operator.
+    InvalidTensor(String),
+    /// The layers don't form a valid network.
+    Network(Error),
+}
+
+impl fmt::Display for OnnxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decode(err) => write!(f, "couldn't decode ONNX model: {err}"),
+            Self::UnsupportedOperator(op) => write!(f, "unsupported ONNX operator: {op}"),
+            Self::InvalidTensor(name) => write!(f, "missing or invalid tensor: {name}"),
+            Self::Network(err) => write!(f, "invalid network: {err}"),
+        }
+    }
+}
+
+impl error::Error for OnnxError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Decode(err) => Some(err),
+            Self::Network(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Network {
+    /// Serializes the network as an ONNX model; fails for networks with
+    /// layers other than dense or softmax ones.
+    pub fn to_onnx(&self) -> Result<Vec<u8>, Error> {
+        let mut graph = proto::Graph {
+            name: "network".into(),
+            input: vec![proto::ValueInfo::matrix(INPUT, self.input_size())],
+            output: vec![proto::ValueInfo::matrix(OUTPUT, self.output_size())],
+            ..Default::default()
+        };
+
+        let mut value = INPUT.to_string();
+
+        for (idx, layer) in self.layers.iter().enumerate() {
+            let name = format!("layer{}", idx + 1);
+
+            match layer {
+                Layer::Dense(layer) => {
+                    let (input_size, output_size) = (layer.input_size(), layer.output_size());
+                    let mut biases = Vec::with_capacity(output_size);
+                    let mut weights = Vec::with_capacity(output_size * input_size);
+
+                    for neuron in layer
+                        .weights()
+                        .collect::<Vec<_>>()
+                        .chunks_exact(1 + input_size)
+                    {
+                        biases.push(neuron[0]);
+                        weights.extend_from_slice(&neuron[1..]);
+                    }
+
+                    graph.initializer.push(proto::Tensor::floats(
+                        format!("{name}.weight"),
+                        vec![output_size as i64, input_size as i64],
+                        weights,
+                    ));
+
+                    graph.initializer.push(proto::Tensor::floats(
+                        format!("{name}.bias"),
+                        vec![output_size as i64],
+                        biases,
+                    ));
+
+                    let sum = format!("{name}.sum");
+
+                    graph.node.push(
+                        proto::Node::new(
+                            "Gemm",
+                            vec![value, format!("{name}.weight"), format!("{name}.bias")],
+                            &sum,
+                        )
+                        .with_int("transB", 1),
+                    );
+
+                    value = push_activation(&mut graph, &name, layer.activation(), sum);
+                }
+
+                Layer::Softmax(_) => {
+                    let output = format!("{name}.output");
+
+                    graph.node.push(
+                        proto::Node::new("Softmax", vec![value], &output).with_int("axis", -1),
+                    );
+
+                    value = output;
+                }
+
+                _ => {
+                    return Err(Error::UnsupportedLayer {
+                        layer: idx + 1,
+                        kind: layer.kind(),
+                    })
+                }
+            }
+        }
+
+        graph
+            .node
+            .push(proto::Node::new("Identity", vec![value], OUTPUT));
+
+        let model = proto::Model {
+            ir_version: IR_VERSION,
+            producer_name: env!("CARGO_PKG_NAME").into(),
+            producer_version: env!("CARGO_PKG_VERSION").into(),
+            graph: Some(graph),
+            opset_import: vec![proto::OperatorSetId {
+                domain: String::new(),
+                version: OPSET_VERSION,
+            }],
+        };
+
+        Ok(model.encode_to_vec())
+    }
+
+    /// Reads a model created by `to_onnx` (or one following the same
+    /// conventions).
+    pub fn from_onnx(bytes: &[u8]) -> Result<Self, OnnxError> {
+        let model = proto::Model::decode(bytes).map_err(OnnxError::Decode)?;
+        let graph = model.graph.unwrap_or_default();
+
+        let tensors: HashMap<_, _> = graph
+            .initializer
+            .iter()
+            .map(|tensor| (tensor.name.as_str(), tensor))
+            .collect();
+
+        let tensor = |name: &str, dims: usize| {
+            tensors
+                .get(name)
+                .filter(|tensor| tensor.dims.len() == dims)
+                .ok_or_else(|| OnnxError::InvalidTensor(name.into()))
+        };
+
+        let is_zero = |name: Option<&String>| {
+            name.and_then(|name| tensors.get(name.as_str()))
+                .is_some_and(|tensor| tensor.dims.is_empty() && tensor.float_data == [0.0])
+        };
+
+        let input = graph
+            .input
+            .first()
+            .ok_or_else(|| OnnxError::InvalidTensor(INPUT.into()))?;
+
+        let input_size = input
+            .columns()
+            .ok_or_else(|| OnnxError::InvalidTensor(input.name.clone()))?;
+
+        let mut topology = vec![LayerTopology::new(input_size)];
+        let mut weights = Vec::new();
+        let mut ops = graph.node.iter().peekable();
+        let mut awaits_activation = false;
+
+        // Name of the value computed so far - each operator has to consume
+        // its predecessor's output
+        let mut value = &input.name;
+
+        while let Some(node) = ops.next() {
+            if node.input.first() != Some(value) {
+                return Err(OnnxError::InvalidTensor(value.clone()));
+            }
+
+            value = output(node)?;
+
+            // Consumes the next node if it's of given type and reads `value`
+            let mut next_is = |op_type: &str| -> Result<Option<&proto::Node>, OnnxError> {
+                let Some(node) = ops
+                    .next_if(|node| node.op_type == op_type && node.input.first() == Some(value))
+                else {
+                    return Ok(None);
+                };
+
+                value = output(node)?;
+                Ok(Some(node))
+            };
+
+            let activation = match node.op_type.as_str() {
+                "Gemm" => {
+                    let weight = tensor(node.input.get(1).map_or("", String::as_str), 2)?;
+                    let bias = tensor(node.input.get(2).map_or("", String::as_str), 1)?;
+                    let (neurons, inputs) = (dim(weight, 0)?, dim(weight, 1)?);
+
+                    if node.int("transA").unwrap_or(0) != 0
+                        || node.int("transB") != Some(1)
+                        || node.float("alpha").unwrap_or(1.0) != 1.0
+                        || node.float("beta").unwrap_or(1.0) != 1.0
+                    {
+                        return Err(OnnxError::UnsupportedOperator(
+                            "Gemm with transposition or scaling other than the exported ones"
+                                .into(),
+                        ));
+                    }
+
+                    if inputs != topology[topology.len() - 1].neurons
+                        || Some(weight.float_data.len()) != neurons.checked_mul(inputs)
+                        || dim(bias, 0)? != neurons
+                        || bias.float_data.len() != neurons
+                    {
+                        return Err(OnnxError::InvalidTensor(weight.name.clone()));
+                    }
+
+                    for (bias, row) in bias
+                        .float_data
+                        .iter()
+                        .zip(weight.float_data.chunks_exact(inputs))
+                    {
+                        weights.push(*bias);
+                        weights.extend_from_slice(row);
+                    }
+
+                    topology
+                        .push(LayerTopology::new(neurons).with_activation(Activation::Identity));
+                    awaits_activation = true;
+                    continue;
+                }
+
+                // Inputs are matrices, so these all mean the last axis
+                "Softmax" if matches!(node.int("axis"), None | Some(-1) | Some(1)) => {
+                    let neurons = topology[topology.len() - 1].neurons;
+
+                    topology.push(LayerTopology::new(neurons).with_kind(LayerKind::Softmax));
+                    awaits_activation = false;
+                    continue;
+                }
+
+                // The trailing `Identity` only renames the last value
+                "Identity" if node.output.first().map(String::as_str) == Some(OUTPUT) => continue,
+                "Identity" => Activation::Identity,
+                "Relu" => Activation::ReLU,
+                "LeakyRelu" => Activation::LeakyReLU(node.float("alpha").unwrap_or(0.01)),
+                "Sigmoid" => Activation::Sigmoid,
+                "Tanh" => Activation::Tanh,
+                "Softsign" => Activation::Softsign,
+                "Sin" => Activation::Sin,
+                "Greater"
+                    if is_zero(node.input.get(1))
+                        && next_is("Cast")?.is_some_and(|cast| {
+                            cast.int("to") == Some(proto::Tensor::FLOAT as i64)
+                        }) =>
+                {
+                    Activation::Step
+                }
+                "Mul"
+                    if node.input.get(1) == node.input.first()
+                        && next_is("Neg")?.is_some()
+                        && next_is("Exp")?.is_some() =>
+                {
+                    Activation::Gaussian
+                }
+                op => return Err(OnnxError::UnsupportedOperator(op.into())),
+            };
+
+            // Each dense layer gets exactly one activation
+            if !std::mem::replace(&mut awaits_activation, false) {
+                return Err(OnnxError::UnsupportedOperator(node.op_type.clone()));
+            }
+
+            let layer = topology.len() - 1;
+
+            topology[layer].activation = activation;
+        }
+
+        if graph.output.first().map(|output| &output.name) != Some(value) {
+            return Err(OnnxError::InvalidTensor(OUTPUT.into()));
+        }
+
+        Self::try_from_weights(&topology, weights).map_err(OnnxError::Network)
+    }
+}
+
+/// Name of the node's (single) output.
+fn output(node: &proto::Node) -> Result<&String, OnnxError> {
+    node.output
+        .first()
+        .ok_or_else(|| OnnxError::UnsupportedOperator(node.op_type.clone()))
+}
+
+/// Size of the tensor along given axis; ONNX stores it as `i64`, so
+/// non-positive values are rejected here.
+fn dim(tensor: &proto::Tensor, axis: usize) -> Result<usize, OnnxError> {
+    tensor
+        .dims
+        .get(axis)
+        .and_then(|&dim| usize::try_from(dim).ok())
+        .filter(|&dim| dim > 0)
+        .ok_or_else(|| OnnxError::InvalidTensor(tensor.name.clone()))
+}
+
+/// Appends nodes applying `activation` to `value`; returns the name of
+/// their output.
+fn push_activation(
+    graph: &mut proto::Graph,
+    name: &str,
+    activation: Activation,
+    value: String,
+) -> String {
+    let output = format!("{name}.output");
+
+    let simple = |op_type| proto::Node::new(op_type, vec![value.clone()], &output);
+
+    let nodes = match activation {
+        Activation::ReLU => vec![simple("Relu")],
+        Activation::LeakyReLU(slope) => vec![simple("LeakyRelu").with_float("alpha", slope)],
+        Activation::Sigmoid => vec![simple("Sigmoid")],
+        Activation::Tanh => vec![simple("Tanh")],
+        Activation::Identity => vec![simple("Identity")],
+        Activation::Softsign => vec![simple("Softsign")],
+        Activation::Sin => vec![simple("Sin")],
+
+        Activation::Step => {
+            let zero = format!("{name}.zero");
+            let positive = format!("{name}.positive");
+
+            graph
+                .initializer
+                .push(proto::Tensor::floats(zero.clone(), Vec::new(), vec![0.0]));
+
+            vec![
+                proto::Node::new("Greater", vec![value, zero], &positive),
+                proto::Node::new("Cast", vec![positive], &output)
+                    .with_int("to", proto::Tensor::FLOAT as i64),
+            ]
+        }
+
+        Activation::Gaussian => {
+            let squared = format!("{name}.squared");
+            let negated = format!("{name}.negated");
+
+            vec![
+                proto::Node::new("Mul", vec![value.clone(), value], &squared),
+                proto::Node::new("Neg", vec![squared], &negated),
+                proto::Node::new("Exp", vec![negated], &output),
+            ]
+        }
+    };
+
+    graph.node.extend(nodes);
+    output
+}
+
+/// Subset of the ONNX protobuf schema (`onnx.proto3`) needed to describe
+/// feed-forward networks; field tags match the original ones.
+mod proto {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Model {
+        #[prost(int64, tag = "1")]
+        pub ir_version: i64,
+        #[prost(string, tag = "2")]
+        pub producer_name: String,
+        #[prost(string, tag = "3")]
+        pub producer_version: String,
+        #[prost(message, optional, tag = "7")]
+        pub graph: Option<Graph>,
+        #[prost(message, repeated, tag = "8")]
+        pub opset_import: Vec<OperatorSetId>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct OperatorSetId {
+        #[prost(string, tag = "1")]
+        pub domain: String,
+        #[prost(int64, tag = "2")]
+        pub version: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Graph {
+        #[prost(message, repeated, tag = "1")]
+        pub node: Vec<Node>,
+        #[prost(string, tag = "2")]
+        pub name: String,
+        #[prost(message, repeated, tag = "5")]
+        pub initializer: Vec<Tensor>,
+        #[prost(message, repeated, tag = "11")]
+        pub input: Vec<ValueInfo>,
+        #[prost(message, repeated, tag = "12")]
+        pub output: Vec<ValueInfo>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Node {
+        #[prost(string, repeated, tag = "1")]
+        pub input: Vec<String>,
+        #[prost(string, repeated, tag = "2")]
+        pub output: Vec<String>,
+        #[prost(string, tag = "4")]
+        pub op_type: String,
+        #[prost(message, repeated, tag = "5")]
+        pub attribute: Vec<Attribute>,
+    }
+
+    impl Node {
+        pub fn new(op_type: &str, input: Vec<String>, output: &str) -> Self {
+            Self {
+                input,
+                output: vec![output.into()],
+                op_type: op_type.into(),
+                attribute: Vec::new(),
+            }
+        }
+
+        pub fn with_int(mut self, name: &str, value: i64) -> Self {
+            self.attribute.push(Attribute {
+                name: name.into(),
+                r#type: Attribute::INT,
+                i: value,
+                ..Default::default()
+            });
+
+            self
+        }
+
+        pub fn with_float(mut self, name: &str, value: f32) -> Self {
+            self.attribute.push(Attribute {
+                name: name.into(),
+                r#type: Attribute::FLOAT,
+                f: value,
+                ..Default::default()
+            });
+
+            self
+        }
+
+        pub fn int(&self, name: &str) -> Option<i64> {
+            self.attribute
+                .iter()
+                .find(|attr| attr.name == name && attr.r#type == Attribute::INT)
+                .map(|attr| attr.i)
+        }
+
+        pub fn float(&self, name: &str) -> Option<f32> {
+            self.attribute
+                .iter()
+                .find(|attr| attr.name == name && attr.r#type == Attribute::FLOAT)
+                .map(|attr| attr.f)
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Attribute {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(float, tag = "2")]
+        pub f: f32,
+        #[prost(int64, tag = "3")]
+        pub i: i64,
+        #[prost(int32, tag = "20")]
+        pub r#type: i32,
+    }
+
+    impl Attribute {
+        pub const FLOAT: i32 = 1;
+        pub const INT: i32 = 2;
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Tensor {
+        #[prost(int64, repeated, tag = "1")]
+        pub dims: Vec<i64>,
+        #[prost(int32, tag = "2")]
+        pub data_type: i32,
+        #[prost(float, repeated, tag = "4")]
+        pub float_data: Vec<f32>,
+        #[prost(string, tag = "8")]
+        pub name: String,
+    }
+
+    impl Tensor {
+        pub const FLOAT: i32 = 1;
+
+        pub fn floats(name: String, dims: Vec<i64>, float_data: Vec<f32>) -> Self {
+            Self {
+                dims,
+                data_type: Self::FLOAT,
+                float_data,
+                name,
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ValueInfo {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(message, optional, tag = "2")]
+        pub r#type: Option<Type>,
+    }
+
+    impl ValueInfo {
+        /// Float tensor of shape `[N, columns]`.
+        pub fn matrix(name: &str, columns: usize) -> Self {
+            Self {
+                name: name.into(),
+                r#type: Some(Type {
+                    tensor_type: Some(TensorType {
+                        elem_type: Tensor::FLOAT,
+                        shape: Some(Shape {
+                            dim: vec![
+                                Dimension {
+                                    value: Some(DimensionValue::Param("N".into())),
+                                },
+                                Dimension {
+                                    value: Some(DimensionValue::Value(columns as i64)),
+                                },
+                            ],
+                        }),
+                    }),
+                }),
+            }
+        }
+
+        /// Inverse of `matrix`.
+        pub fn columns(&self) -> Option<usize> {
+            let shape = self.r#type.as_ref()?.tensor_type.as_ref()?.shape.as_ref()?;
+
+            match shape.dim.as_slice() {
+                [_, Dimension {
+                    value: Some(DimensionValue::Value(columns)),
+                }] => usize::try_from(*columns)
+                    .ok()
+                    .filter(|&columns| columns > 0),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Type {
+        #[prost(message, optional, tag = "1")]
+        pub tensor_type: Option<TensorType>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TensorType {
+        #[prost(int32, tag = "1")]
+        pub elem_type: i32,
+        #[prost(message, optional, tag = "2")]
+        pub shape: Option<Shape>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Shape {
+        #[prost(message, repeated, tag = "1")]
+        pub dim: Vec<Dimension>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Dimension {
+        #[prost(oneof = "DimensionValue", tags = "1, 2")]
+        pub value: Option<DimensionValue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum DimensionValue {
+        #[prost(int64, tag = "1")]
+        Value(i64),
+        #[prost(string, tag = "2")]
+        Param(String),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn network(activations: &[Activation]) -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let topology: Vec<_> = std::iter::once(LayerTopology::new(3))
+            .chain(
+                activations
+                    .iter()
+                    .map(|&activation| LayerTopology::new(4).with_activation(activation)),
+            )
+            .collect();
+
+        Network::random(&mut rng, &topology)
+    }
+
+    mod round_trip {
+        use super::*;
+
+        #[test]
+        fn all_activations() {
+            let network = network(&[
+                Activation::ReLU,
+                Activation::LeakyReLU(0.2),
+                Activation::Sigmoid,
+                Activation::Tanh,
+                Activation::Identity,
+                Activation::Softsign,
+                Activation::Step,
+                Activation::Sin,
+                Activation::Gaussian,
+            ]);
+
+            let actual = Network::from_onnx(&network.to_onnx().unwrap()).unwrap();
+
+            assert_eq!(actual, network);
+        }
+
+        #[test]
+        fn softmax() {
+            let mut network = Network::from_weights(
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(3).with_activation(Activation::Tanh),
+                    LayerTopology::new(3).with_kind(LayerKind::Softmax),
+                ],
+                vec![0.1, 0.2, 0.3, -0.4, 0.5, 0.6, 0.7, -0.8, 0.9],
+            );
+
+            let mut actual = Network::from_onnx(&network.to_onnx().unwrap()).unwrap();
+
+            assert_eq!(actual, network);
+
+            let expected = network.propagate(vec![0.5, -1.0]);
+            let actual = actual.propagate(vec![0.5, -1.0]);
+
+            assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+    }
+
+    /// Checks the exported models against an independent ONNX runtime.
+    mod tract {
+        use super::*;
+        use tract_onnx::prelude::*;
+
+        fn assert_tract_matches_propagate(network: &Network) {
+            let inputs = [0.5, -1.0, 0.25, -0.3, 2.0, 0.0];
+            let batch_size = inputs.len() / network.input_size();
+
+            let model = tract_onnx::onnx()
+                .model_for_read(&mut network.to_onnx().unwrap().as_slice())
+                .unwrap()
+                .with_input_fact(0, f32::fact([batch_size, network.input_size()]).into())
+                .unwrap()
+                // The output's batch dimension is symbolic; let tract infer it
+                .with_output_fact(0, InferenceFact::default())
+                .unwrap()
+                .into_optimized()
+                .unwrap()
+                .into_runnable()
+                .unwrap();
+
+            let input = Tensor::from_shape(&[batch_size, network.input_size()], &inputs).unwrap();
+            let outputs = model.run(tvec!(input.into())).unwrap();
+
+            let actual = outputs[0].as_slice::<f32>().unwrap();
+            let expected = network.propagate_batch(&inputs);
+
+            assert_relative_eq!(actual, expected.as_slice(), epsilon = 1e-5);
+        }
+
+        #[test]
+        fn all_activations() {
+            for activation in [
+                Activation::ReLU,
+                Activation::LeakyReLU(0.2),
+                Activation::Sigmoid,
+                Activation::Tanh,
+                Activation::Identity,
+                Activation::Softsign,
+                Activation::Step,
+                Activation::Sin,
+                Activation::Gaussian,
+            ] {
+                assert_tract_matches_propagate(&network(&[activation, activation]));
+            }
+        }
+
+        #[test]
+        fn softmax() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(3),
+                    LayerTopology::new(4).with_activation(Activation::Tanh),
+                    LayerTopology::new(4).with_kind(LayerKind::Softmax),
+                ],
+            );
+
+            assert_tract_matches_propagate(&network);
+        }
+    }
+
+    mod to_onnx {
+        use super::*;
+
+        #[test]
+        fn rejects_recurrent_layers() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology::new(1),
+                    LayerTopology::new(1).with_kind(LayerKind::Recurrent),
+                ],
+                vec![0.0; 3],
+            );
+
+            assert_eq!(
+                network.to_onnx().unwrap_err(),
+                Error::UnsupportedLayer {
+                    layer: 1,
+                    kind: LayerKind::Recurrent
+                }
+            );
+        }
+
+        #[test]
+        fn graph() {
+            let bytes = network(&[Activation::Step]).to_onnx().unwrap();
+            let model = proto::Model::decode(bytes.as_slice()).unwrap();
+            let graph = model.graph.unwrap();
+
+            let ops: Vec<_> = graph
+                .node
+                .iter()
+                .map(|node| node.op_type.as_str())
+                .collect();
+
+            assert_eq!(ops, ["Gemm", "Greater", "Cast", "Identity"]);
+            assert_eq!(graph.input[0].columns(), Some(3));
+            assert_eq!(graph.output[0].columns(), Some(4));
+            assert_eq!(graph.initializer[0].dims, [4, 3]);
+        }
+    }
+
+    mod from_onnx {
+        use super::*;
+
+        /// Exports a network, lets `f` tamper with its graph and imports it
+        /// back.
+        fn import_tampered(f: impl FnOnce(&mut proto::Graph)) -> Result<Network, OnnxError> {
+            tamper(&network(&[Activation::Tanh]), f)
+        }
+
+        fn tamper(
+            network: &Network,
+            f: impl FnOnce(&mut proto::Graph),
+        ) -> Result<Network, OnnxError> {
+            let bytes = network.to_onnx().unwrap();
+            let mut model = proto::Model::decode(bytes.as_slice()).unwrap();
+
+            f(model.graph.as_mut().unwrap());
+
+            Network::from_onnx(&model.encode_to_vec())
+        }
+
+        #[test]
+        fn rejects_garbage() {
+            assert!(matches!(
+                Network::from_onnx(&[0xff, 0xff, 0xff]),
+                Err(OnnxError::Decode(_))
+            ));
+        }
+
+        #[test]
+        fn rejects_non_positive_dims() {
+            for dims in [[4, 0], [-1, 3], [4, -3]] {
+                let result = import_tampered(|graph| {
+                    graph.initializer[0].dims = dims.to_vec();
+                    graph.initializer[0].float_data.clear();
+                });
+
+                assert!(matches!(result, Err(OnnxError::InvalidTensor(_))));
+            }
+
+            let result = import_tampered(|graph| {
+                graph.input[0] = proto::ValueInfo::matrix(INPUT, 0);
+            });
+
+            assert!(matches!(result, Err(OnnxError::InvalidTensor(_))));
+        }
+
+        #[test]
+        fn rejects_mismatched_layers() {
+            // Consistent on its own, but for 6 inputs instead of 3
+            let result = import_tampered(|graph| {
+                graph.initializer[0].dims = vec![2, 6];
+                graph.initializer[1].dims = vec![2];
+                graph.initializer[1].float_data.truncate(2);
+            });
+
+            assert!(matches!(result, Err(OnnxError::InvalidTensor(_))));
+        }
+
+        #[test]
+        fn rejects_scaled_gemms() {
+            let result = import_tampered(|graph| {
+                graph.node[0] = graph.node[0].clone().with_float("alpha", 2.0);
+            });
+
+            assert!(matches!(result, Err(OnnxError::UnsupportedOperator(_))));
+        }
+
+        #[test]
+        fn rejects_other_step_functions() {
+            let network = network(&[Activation::Step]);
+
+            let result = tamper(&network, |graph| {
+                graph.initializer[2].float_data = vec![0.5];
+            });
+
+            assert!(matches!(result, Err(OnnxError::UnsupportedOperator(_))));
+
+            let result = tamper(&network, |graph| {
+                graph.node[2].attribute[0].i = 7;
+            });
+
+            assert!(matches!(result, Err(OnnxError::UnsupportedOperator(_))));
+        }
+
+        #[test]
+        fn rejects_other_softmax_axes() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(2).with_kind(LayerKind::Softmax),
+                ],
+                Vec::new(),
+            );
+
+            let result = tamper(&network, |graph| graph.node[0].attribute[0].i = 0);
+
+            assert!(matches!(result, Err(OnnxError::UnsupportedOperator(_))));
+
+            let result = tamper(&network, |graph| graph.node[0].attribute.clear());
+
+            assert_eq!(result.unwrap(), network);
+        }
+
+        #[test]
+        fn rejects_disconnected_nodes() {
+            let result = import_tampered(|graph| graph.node[1].input[0] = "layer0.sum".into());
+
+            assert!(matches!(result, Err(OnnxError::InvalidTensor(_))));
+        }
+    }
+}
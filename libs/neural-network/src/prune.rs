@@ -0,0 +1,322 @@
+use crate::*;
+
+impl Network {
+    /// Zeroes all the weights (biases excluded) whose magnitude is below
+    /// `min_weight`; returns how many weights got zeroed.
+    pub fn prune(&mut self, min_weight: f32) -> usize {
+        let mut pruned = 0;
+
+        for layer in &mut self.layers {
+            let topology = layer.topology();
+            let block = 1 + topology.fan_in(layer.input_size());
+            let count = topology.blocks(layer.input_size()) * block;
+
+            // Plastic layers' rules come after the weights and are left as-is
+            for (idx, weight) in layer.weights_mut().take(count).enumerate() {
+                if idx % block != 0 && *weight != 0.0 && weight.abs() < min_weight {
+                    *weight = 0.0;
+                    pruned += 1;
+                }
+            }
+        }
+
+        pruned
+    }
+
+    /// Removes a hidden neuron that always outputs `value`, folding its
+    /// contribution into the biases of the neurons that read it.
+    ///
+    /// Layers are numbered as in the topology and the neuron's layer has to
+    /// be a dense or recurrent one. Recurrent state is reset; since a fresh
+    /// state is zero rather than `value`, the very first step after a reset
+    /// can differ for recurrent layers, unless `value` is zero.
+    pub fn remove_neuron(&mut self, layer: usize, neuron: usize, value: f32) -> Result<(), Error> {
+        let mut topology = self.topology();
+
+        if layer == 0 || layer >= topology.len() - 1 {
+            return Err(Error::NotAHiddenLayer(layer));
+        }
+
+        let kind = topology[layer].kind;
+
+        if !matches!(kind, LayerKind::Dense | LayerKind::Recurrent) {
+            return Err(Error::UnsupportedLayer { layer, kind });
+        }
+
+        if neuron >= topology[layer].neurons {
+            return Err(Error::NeuronOutOfRange { layer, neuron });
+        }
+
+        if topology[layer].neurons == 1 {
+            return Err(Error::EmptyLayer { layer });
+        }
+
+        // Only these layers sum their inputs linearly, so that a constant
+        // input can be folded into the bias
+        let next = topology[layer + 1].kind;
+
+        if !matches!(
+            next,
+            LayerKind::Dense | LayerKind::Recurrent | LayerKind::Lstm | LayerKind::Gru
+        ) {
+            return Err(Error::UnsupportedLayer {
+                layer: layer + 1,
+                kind: next,
+            });
+        }
+
+        let mut source = self.weights();
+        let mut weights = Vec::with_capacity(Self::weight_count(&topology));
+
+        for (idx, pair) in topology.windows(2).enumerate() {
+            let current = idx + 1;
+            let input_size = pair[0].neurons;
+
+            if current != layer && current != layer + 1 {
+                weights.extend(
+                    source
+                        .by_ref()
+                        .take(pair[1].weight_count(input_size).unwrap()),
+                );
+                continue;
+            }
+
+            let recurrent_size = pair[1].fan_in(input_size) - input_size;
+
+            for _ in 0..pair[1].kind.gates() {
+                for n in 0..pair[1].neurons {
+                    let mut bias = source.next().unwrap();
+                    let inputs: Vec<_> = source.by_ref().take(input_size).collect();
+                    let recurrent: Vec<_> = source.by_ref().take(recurrent_size).collect();
+
+                    if current == layer && n == neuron {
+                        continue;
+                    }
+
+                    let removed_input = current == layer + 1;
+                    let removed_recurrent = current == layer && recurrent_size > 0;
+
+                    if removed_input {
+                        bias += inputs[neuron] * value;
+                    }
+
+                    if removed_recurrent {
+                        bias += recurrent[neuron] * value;
+                    }
+
+                    weights.push(bias);
+
+                    for (idx, &weight) in inputs.iter().enumerate() {
+                        if !(removed_input && idx == neuron) {
+                            weights.push(weight);
+                        }
+                    }
+
+                    for (idx, &weight) in recurrent.iter().enumerate() {
+                        if !(removed_recurrent && idx == neuron) {
+                            weights.push(weight);
+                        }
+                    }
+                }
+            }
+        }
+
+        topology[layer].neurons -= 1;
+        drop(source);
+        *self = Self::from_weights(&topology, weights);
+
+        Ok(())
+    }
+
+    /// Removes hidden neurons whose outputs (as recorded in `stats`) varied
+    /// by no more than `tolerance` - e.g. dead ReLUs - treating them as
+    /// constants equal to their mean; returns how many neurons got removed.
+    ///
+    /// Gated layers are skipped and each layer keeps at least one neuron.
+    /// Fails when `stats` were recorded for a network of another shape. See
+    /// `remove_neuron`.
+    pub fn remove_constant_neurons(
+        &mut self,
+        stats: &ActivationStats,
+        tolerance: f32,
+    ) -> Result<usize, Error> {
+        let mut removed = 0;
+
+        if stats.calls() == 0 {
+            return Ok(0);
+        }
+
+        if !stats.matches(self) {
+            return Err(Error::TopologyMismatch);
+        }
+
+        // Stats skip the input layer, so their layer #0 is topology's #1
+        for layer in 1..self.layers.len() {
+            let neurons: Vec<_> = stats.layer(layer - 1).collect();
+
+            for (neuron, stats) in neurons.iter().enumerate().rev() {
+                if stats.max - stats.min > tolerance {
+                    continue;
+                }
+
+                match self.remove_neuron(layer, neuron, stats.mean) {
+                    Ok(()) => removed += 1,
+                    Err(Error::UnsupportedLayer { .. }) => break,
+                    Err(Error::EmptyLayer { .. }) => continue,
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn network() -> Network {
+        Network::new(vec![
+            Layer::Dense(DenseLayer::new(
+                vec![0.0, -10.0, 0.5],
+                vec![1.0, 0.05, 1.0, 1.0, 0.0, 0.0],
+                Activation::ReLU,
+            )),
+            Layer::Recurrent(RecurrentLayer::new(
+                vec![0.1, 0.2],
+                vec![0.5, 2.0, -1.0, 1.0, -0.5, 3.0],
+                vec![0.25, -0.01, 0.5, 0.125],
+                Activation::Tanh,
+            )),
+            Layer::Dense(DenseLayer::new(
+                vec![0.0],
+                vec![1.0, -1.0],
+                Activation::Identity,
+            )),
+        ])
+    }
+
+    fn inputs() -> [[f32; 2]; 4] {
+        [[1.0, 2.0], [0.5, -0.5], [-1.0, 3.0], [2.0, 1.0]]
+    }
+
+    mod prune {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut network = network();
+
+            assert_eq!(network.prune(0.1), 2);
+
+            let actual: Vec<_> = network.weights().collect();
+
+            assert_eq!(
+                &actual[..9],
+                &[0.0, 1.0, 0.0, -10.0, 1.0, 1.0, 0.5, 0.0, 0.0]
+            );
+            assert_eq!(&actual[9..15], &[0.1, 0.5, 2.0, -1.0, 0.25, 0.0]);
+        }
+    }
+
+    mod remove_neuron {
+        use super::*;
+
+        #[test]
+        fn folds_constant_neurons() {
+            // The third neuron has no weights, so it always outputs 0.5
+            let mut network = network();
+            let mut reference = network.clone();
+
+            network.remove_neuron(1, 2, 0.5).unwrap();
+
+            assert_eq!(network.layers[0].output_size(), 2);
+            assert_eq!(network.layers[1].input_size(), 2);
+
+            for inputs in inputs() {
+                let expected = reference.propagate(inputs.to_vec());
+                let actual = network.propagate(inputs.to_vec());
+
+                assert_relative_eq!(actual.as_slice(), expected.as_slice(), epsilon = 1e-6);
+            }
+        }
+
+        #[test]
+        fn folds_recurrent_neurons() {
+            let mut network = network();
+
+            network.remove_neuron(2, 1, 0.0).unwrap();
+
+            let actual: Vec<_> = network.layers[1].weights().collect();
+
+            assert_relative_eq!(actual.as_slice(), [0.1, 0.5, 2.0, -1.0, 0.25].as_slice());
+            assert_eq!(network.layers[2].input_size(), 1);
+        }
+
+        #[test]
+        fn rejects_invalid_neurons() {
+            let mut network = network();
+
+            assert_eq!(
+                network.remove_neuron(0, 0, 0.0),
+                Err(Error::NotAHiddenLayer(0))
+            );
+
+            assert_eq!(
+                network.remove_neuron(3, 0, 0.0),
+                Err(Error::NotAHiddenLayer(3))
+            );
+
+            assert_eq!(
+                network.remove_neuron(1, 3, 0.0),
+                Err(Error::NeuronOutOfRange {
+                    layer: 1,
+                    neuron: 3
+                })
+            );
+        }
+    }
+
+    mod remove_constant_neurons {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut network = network();
+            let mut reference = network.clone();
+            let mut stats = ActivationStats::new();
+
+            for inputs in inputs() {
+                stats.record(&network.clone().propagate_traced(&inputs));
+            }
+
+            // The dead second neuron and the constant third one
+            assert_eq!(network.remove_constant_neurons(&stats, 1e-6), Ok(2));
+            assert_eq!(network.layers[0].output_size(), 1);
+
+            for inputs in inputs() {
+                let expected = reference.propagate(inputs.to_vec());
+                let actual = network.propagate(inputs.to_vec());
+
+                assert_relative_eq!(actual.as_slice(), expected.as_slice(), epsilon = 1e-6);
+            }
+        }
+
+        #[test]
+        fn rejects_other_networks() {
+            let mut network = network();
+            let mut other = network.clone();
+            let mut stats = ActivationStats::new();
+
+            other.remove_neuron(1, 0, 0.0).unwrap();
+            stats.record(&other.propagate_traced(&[1.0, 2.0]));
+
+            assert_eq!(
+                network.remove_constant_neurons(&stats, 1e-6),
+                Err(Error::TopologyMismatch)
+            );
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use crate::*;
+
+/// Network whose weights are stored as 8-bit integers - about a quarter of
+/// the original size, at the cost of some precision.
+///
+/// Each neuron's weights share a single scale (their largest magnitude maps
+/// to 127) and biases stay as `f32`. While propagating, each layer's inputs
+/// and recurrent state get quantized the same way, so that neurons sum up
+/// their inputs in `i32`.
+#[derive(Clone, Debug)]
+pub struct QuantizedNetwork {
+    layers: Vec<QuantizedLayer>,
+    /// Outputs of the previous and the current hidden layer.
+    front: Vec<f32>,
+    back: Vec<f32>,
+}
+
+#[derive(Clone, Debug)]
+struct QuantizedLayer {
+    input_size: usize,
+    activation: Activation,
+    biases: Vec<f32>,
+    scales: Vec<f32>,
+    /// Per neuron: input weights and then (for recurrent layers) recurrent
+    /// weights.
+    weights: Vec<i8>,
+    /// Empty for dense layers.
+    state: Vec<f32>,
+    /// Quantized inputs and then state, as of the last `propagate_into`.
+    codes: Vec<i8>,
+}
+
+impl Network {
+    /// Only dense and recurrent layers are supported.
+    pub fn quantize(&self) -> Result<QuantizedNetwork, Error> {
+        let layers: Vec<_> = self
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(idx, layer)| QuantizedLayer::new(idx + 1, layer))
+            .collect::<Result<_, _>>()?;
+
+        let size = layers
+            .iter()
+            .map(|layer| layer.input_size.max(layer.biases.len()))
+            .max()
+            .unwrap_or(0);
+
+        Ok(QuantizedNetwork {
+            layers,
+            front: vec![0.0; size],
+            back: vec![0.0; size],
+        })
+    }
+}
+
+impl QuantizedNetwork {
+    pub fn input_size(&self) -> usize {
+        self.layers[0].input_size
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.layers[self.layers.len() - 1].biases.len()
+    }
+
+    pub fn propagate(&mut self, inputs: &[f32]) -> Vec<f32> {
+        let mut outputs = vec![0.0; self.output_size()];
+        self.propagate_into(inputs, &mut outputs);
+        outputs
+    }
+
+    /// Like `propagate`, but performs no allocations.
+    pub fn propagate_into(&mut self, inputs: &[f32], outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.input_size());
+        assert_eq!(outputs.len(), self.output_size());
+
+        let (last, hidden) = self.layers.split_last_mut().unwrap();
+        let mut len = inputs.len();
+
+        self.front[..len].copy_from_slice(inputs);
+
+        for layer in hidden {
+            let output_size = layer.biases.len();
+
+            layer.propagate_into(&self.front[..len], &mut self.back[..output_size]);
+            std::mem::swap(&mut self.front, &mut self.back);
+            len = output_size;
+        }
+
+        last.propagate_into(&self.front[..len], outputs);
+    }
+
+    pub fn reset(&mut self) {
+        for layer in &mut self.layers {
+            layer.state.fill(0.0);
+        }
+    }
+
+    /// Converts back into a regular network, with weights as they're seen
+    /// by `propagate` (which, unlike the returned network, also quantizes
+    /// inputs and states).
+    pub fn dequantize(&self) -> Network {
+        let mut topology = vec![LayerTopology::new(self.input_size())];
+        let mut weights = Vec::new();
+
+        for layer in &self.layers {
+            let kind = if layer.state.is_empty() {
+                LayerKind::Dense
+            } else {
+                LayerKind::Recurrent
+            };
+
+            topology.push(
+                LayerTopology::new(layer.biases.len())
+                    .with_activation(layer.activation)
+                    .with_kind(kind),
+            );
+
+            for (neuron, (bias, scale)) in layer.biases.iter().zip(&layer.scales).enumerate() {
+                weights.push(*bias);
+
+                weights.extend(
+                    layer
+                        .neuron_weights(neuron)
+                        .iter()
+                        .map(|&weight| weight as f32 * scale),
+                );
+            }
+        }
+
+        Network::from_weights(&topology, weights)
+    }
+}
+
+impl QuantizedLayer {
+    fn new(idx: usize, layer: &Layer) -> Result<Self, Error> {
+        let kind = layer.kind();
+
+        if !matches!(kind, LayerKind::Dense | LayerKind::Recurrent) {
+            return Err(Error::UnsupportedLayer { layer: idx, kind });
+        }
+
+        let fan_in = layer.topology().fan_in(layer.input_size());
+        let params: Vec<_> = layer.weights().collect();
+
+        let mut biases = Vec::with_capacity(layer.output_size());
+        let mut scales = Vec::with_capacity(layer.output_size());
+        let mut weights = Vec::with_capacity(layer.output_size() * fan_in);
+
+        for neuron in params.chunks_exact(1 + fan_in) {
+            let (bias, neuron) = neuron.split_first().unwrap();
+            let mut codes = vec![0; neuron.len()];
+
+            biases.push(*bias);
+            scales.push(quantize(neuron, &mut codes));
+            weights.extend(codes);
+        }
+
+        let state = if kind == LayerKind::Recurrent {
+            vec![0.0; layer.output_size()]
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            input_size: layer.input_size(),
+            activation: layer.activation(),
+            biases,
+            scales,
+            weights,
+            codes: vec![0; fan_in],
+            state,
+        })
+    }
+
+    fn neuron_weights(&self, neuron: usize) -> &[i8] {
+        let fan_in = self.input_size + self.state.len();
+
+        &self.weights[neuron * fan_in..][..fan_in]
+    }
+
+    fn propagate_into(&mut self, inputs: &[f32], outputs: &mut [f32]) {
+        let (input_codes, state_codes) = self.codes.split_at_mut(self.input_size);
+        let input_scale = quantize(inputs, input_codes);
+        let state_scale = quantize(&self.state, state_codes);
+
+        for (neuron, output) in outputs.iter_mut().enumerate() {
+            let weights = self.neuron_weights(neuron);
+            let (input_weights, recurrent_weights) = weights.split_at(self.input_size);
+            let (input_codes, state_codes) = self.codes.split_at(self.input_size);
+
+            // 127 * 127 per input, so `i32` holds sums of over 100k inputs
+            let sum = input_scale * dot(input_weights, input_codes) as f32
+                + state_scale * dot(recurrent_weights, state_codes) as f32;
+
+            *output = self
+                .activation
+                .apply(self.biases[neuron] + self.scales[neuron] * sum);
+        }
+
+        if !self.state.is_empty() {
+            self.state.copy_from_slice(outputs);
+        }
+    }
+}
+
+/// Writes `values` into `codes` so that their largest magnitude maps to
+/// 127; returns the scale that turns codes back into values.
+fn quantize(values: &[f32], codes: &mut [i8]) -> f32 {
+    let max = values.iter().fold(0.0f32, |max, v| max.max(v.abs()));
+    let scale = if max > 0.0 { max / 127.0 } else { 1.0 };
+
+    for (code, value) in codes.iter_mut().zip(values) {
+        *code = (value / scale).round() as i8;
+    }
+
+    scale
+}
+
+fn dot(lhs: &[i8], rhs: &[i8]) -> i32 {
+    lhs.iter()
+        .zip(rhs)
+        .map(|(&lhs, &rhs)| lhs as i32 * rhs as i32)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn network() -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(3),
+                LayerTopology::new(4).with_kind(LayerKind::Recurrent),
+                LayerTopology::new(2).with_activation(Activation::Tanh),
+            ],
+        )
+    }
+
+    mod quantize {
+        use super::*;
+
+        #[test]
+        fn approximates_the_network() {
+            let mut network = network();
+            let mut quantized = network.quantize().unwrap();
+
+            for inputs in [[0.5, -0.5, 1.0], [1.0, 0.0, 0.25], [-1.0, 1.0, 0.0]] {
+                let expected = network.propagate(inputs.to_vec());
+                let actual = quantized.propagate(&inputs);
+
+                assert_relative_eq!(actual.as_slice(), expected.as_slice(), epsilon = 0.02);
+            }
+        }
+
+        /// Up to the error of quantizing inputs and states
+        #[test]
+        fn matches_dequantized_network() {
+            let mut quantized = network().quantize().unwrap();
+            let mut dequantized = quantized.dequantize();
+
+            for inputs in [[0.5, -0.5, 1.0], [1.0, 0.0, 0.25]] {
+                let expected = dequantized.propagate(inputs.to_vec());
+                let actual = quantized.propagate(&inputs);
+
+                assert_relative_eq!(actual.as_slice(), expected.as_slice(), epsilon = 1e-3);
+            }
+
+            quantized.reset();
+            dequantized.reset();
+
+            let expected = dequantized.propagate(vec![0.1, 0.2, 0.3]);
+            let actual = quantized.propagate(&[0.1, 0.2, 0.3]);
+
+            assert_relative_eq!(actual.as_slice(), expected.as_slice(), epsilon = 1e-3);
+        }
+
+        #[test]
+        fn propagate_into() {
+            let mut quantized = network().quantize().unwrap();
+            let mut reference = quantized.clone();
+            let mut outputs = [0.0; 2];
+
+            for inputs in [[0.5, -0.5, 1.0], [1.0, 0.0, 0.25]] {
+                quantized.propagate_into(&inputs, &mut outputs);
+
+                assert_eq!(outputs.as_slice(), reference.propagate(&inputs));
+            }
+        }
+
+        #[test]
+        fn rejects_gated_layers() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology::new(1),
+                    LayerTopology::new(1).with_kind(LayerKind::Gru),
+                ],
+                vec![0.0; 9],
+            );
+
+            assert_eq!(
+                network.quantize().unwrap_err(),
+                Error::UnsupportedLayer {
+                    layer: 1,
+                    kind: LayerKind::Gru
+                }
+            );
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::*;
+
+/// Reusable buffers for `Network::propagate_into`, so that propagating
+/// doesn't have to allocate.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkScratch {
+    front: Vec<f32>,
+    back: Vec<f32>,
+}
+
+impl NetworkScratch {
+    /// Creates buffers large enough for any network of given topology.
+    pub fn new(layers: &[LayerTopology]) -> Self {
+        let size = layers.iter().map(|layer| layer.neurons).max().unwrap_or(0);
+
+        Self {
+            front: vec![0.0; size],
+            back: vec![0.0; size],
+        }
+    }
+
+    pub(crate) fn propagate<'a>(&'a mut self, layers: &mut [Layer], inputs: &[f32]) -> &'a [f32] {
+        let mut len = inputs.len();
+
+        for (idx, layer) in layers.iter_mut().enumerate() {
+            let output_size = layer.output_size();
+
+            if self.back.len() < output_size {
+                self.front.resize(output_size, 0.0);
+                self.back.resize(output_size, 0.0);
+            }
+
+            if idx == 0 {
+                layer.propagate_into(inputs, &mut self.back[..output_size]);
+            } else {
+                layer.propagate_into(&self.front[..len], &mut self.back[..output_size]);
+            }
+
+            std::mem::swap(&mut self.front, &mut self.back);
+            len = output_size;
+        }
+
+        &self.front[..len]
+    }
+}
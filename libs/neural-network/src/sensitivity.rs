@@ -0,0 +1,320 @@
+//! How much the network's outputs depend on each of its inputs.
+//!
+//! All the functions here work from the network's current state (which is
+//! left unchanged) - just like `Network::propagate_batch`; for recurrent
+//! layers that state is treated as a constant input.
+
+use crate::*;
+
+/// Step used by `Network::saliency` for layers without gradients.
+const SALIENCY_STEP: f32 = 1e-3;
+
+/// Derivatives of the outputs with respect to the inputs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Jacobian {
+    pub inputs: usize,
+    pub outputs: usize,
+    /// Row-major `outputs x inputs` matrix.
+    pub values: Vec<f32>,
+}
+
+impl Jacobian {
+    /// Derivative of the `output`-th output with respect to the `input`-th
+    /// input.
+    pub fn get(&self, output: usize, input: usize) -> f32 {
+        self.values[output * self.inputs + input]
+    }
+
+    /// Derivatives of the `output`-th output with respect to all inputs.
+    pub fn row(&self, output: usize) -> &[f32] {
+        &self.values[output * self.inputs..][..self.inputs]
+    }
+}
+
+impl Network {
+    /// Computes the jacobian using backpropagation; only networks made of
+    /// dense, recurrent, softmax and layer normalization layers are
+    /// supported.
+    pub fn jacobian(&self, inputs: &[f32]) -> Result<Jacobian, Error> {
+        self.check_input_size(inputs.len())?;
+        self.check_backpropagation()?;
+
+        // activations[0] holds the inputs, activations[n + 1] - outputs of
+        // the n-th layer
+        let mut activations = vec![inputs.to_vec()];
+        let mut sums = Vec::with_capacity(self.layers.len());
+
+        for layer in &self.layers {
+            let mut layer_sums = vec![0.0; layer.output_size()];
+            let mut outputs = vec![0.0; layer.output_size()];
+
+            layer.forward_train(
+                &activations[activations.len() - 1],
+                &mut layer_sums,
+                &mut outputs,
+            );
+            sums.push(layer_sums);
+            activations.push(outputs);
+        }
+
+        let output_size = self.output_size();
+        let mut values = Vec::with_capacity(output_size * inputs.len());
+
+        for output in 0..output_size {
+            let mut deltas = vec![0.0; output_size];
+
+            deltas[output] = 1.0;
+
+            for (idx, layer) in self.layers.iter().enumerate().rev() {
+                let mut input_gradients = vec![0.0; layer.input_size()];
+
+                // Weights' gradients aren't needed, but have to go somewhere
+                let mut gradients = vec![0.0; layer.weight_count()];
+
+                layer.backward(
+                    &activations[idx],
+                    &sums[idx],
+                    &mut deltas,
+                    &mut input_gradients,
+                    &mut gradients,
+                );
+
+                deltas = input_gradients;
+            }
+
+            values.extend(deltas);
+        }
+
+        Ok(Jacobian {
+            inputs: inputs.len(),
+            outputs: output_size,
+            values,
+        })
+    }
+
+    /// Approximates the jacobian with central differences of given `step`;
+    /// works for all kinds of layers.
+    pub fn jacobian_numeric(&self, inputs: &[f32], step: f32) -> Result<Jacobian, Error> {
+        self.check_input_size(inputs.len())?;
+
+        // Both perturbations of each input, propagated as a single batch:
+        // [+step for input 0, -step for input 0, +step for input 1, ...]
+        let mut batch = Vec::with_capacity(2 * inputs.len() * inputs.len());
+
+        for input in 0..inputs.len() {
+            for sign in [1.0, -1.0] {
+                let start = batch.len();
+
+                batch.extend_from_slice(inputs);
+                batch[start + input] += sign * step;
+            }
+        }
+
+        let outputs = self.propagate_batch(&batch);
+        let output_size = self.output_size();
+        let mut values = vec![0.0; output_size * inputs.len()];
+
+        for (input, outputs) in outputs.chunks_exact(2 * output_size).enumerate() {
+            let (plus, minus) = outputs.split_at(output_size);
+
+            for (output, (plus, minus)) in plus.iter().zip(minus).enumerate() {
+                values[output * inputs.len() + input] = (plus - minus) / (2.0 * step);
+            }
+        }
+
+        Ok(Jacobian {
+            inputs: inputs.len(),
+            outputs: output_size,
+            values,
+        })
+    }
+
+    /// Average (over the samples, laid out as in `propagate_batch`) of the
+    /// absolute derivatives of all outputs with respect to each input; the
+    /// higher it is, the more the network relies on given input.
+    ///
+    /// Uses `jacobian` when possible and `jacobian_numeric` otherwise.
+    pub fn saliency(&self, inputs: &[f32]) -> Vec<f32> {
+        let input_size = self.input_size();
+
+        assert_eq!(inputs.len() % input_size, 0);
+
+        let batch_size = inputs.len() / input_size;
+        let mut saliency = vec![0.0; input_size];
+
+        for inputs in inputs.chunks_exact(input_size) {
+            let jacobian = self
+                .jacobian(inputs)
+                .or_else(|_| self.jacobian_numeric(inputs, SALIENCY_STEP))
+                .unwrap();
+
+            for output in 0..jacobian.outputs {
+                for (saliency, value) in saliency.iter_mut().zip(jacobian.row(output)) {
+                    *saliency += value.abs();
+                }
+            }
+        }
+
+        for saliency in &mut saliency {
+            *saliency /= batch_size.max(1) as f32;
+        }
+
+        saliency
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn network() -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(3),
+                LayerTopology::new(4)
+                    .with_activation(Activation::Tanh)
+                    .with_kind(LayerKind::Recurrent),
+                LayerTopology::new(2).with_activation(Activation::Sigmoid),
+            ],
+        );
+
+        // Give the recurrent layer some state
+        network.propagate(vec![0.5, -0.5, 0.25]);
+        network
+    }
+
+    mod jacobian {
+        use super::*;
+
+        #[test]
+        fn matches_finite_differences() {
+            let network = network();
+            let inputs = [0.3, -0.8, 0.1];
+
+            let actual = network.jacobian(&inputs).unwrap();
+            let expected = network.jacobian_numeric(&inputs, 1e-2).unwrap();
+
+            assert_eq!((actual.outputs, actual.inputs), (2, 3));
+            assert_relative_eq!(
+                actual.values.as_slice(),
+                expected.values.as_slice(),
+                epsilon = 1e-3
+            );
+        }
+
+        #[test]
+        fn rejects_unsupported_layers() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(2).with_kind(LayerKind::Lstm),
+                ],
+            );
+
+            assert_eq!(
+                network.jacobian(&[0.0, 0.0]).unwrap_err(),
+                Error::UnsupportedLayer {
+                    layer: 1,
+                    kind: LayerKind::Lstm
+                }
+            );
+        }
+    }
+
+    mod jacobian_numeric {
+        use super::*;
+
+        #[test]
+        fn test() {
+            // y0 = x0 + 2 * x1, y1 = -x1
+            let network = Network::from_weights(
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(2).with_activation(Activation::Identity),
+                ],
+                vec![0.0, 1.0, 2.0, 0.5, 0.0, -1.0],
+            );
+
+            let jacobian = network.jacobian_numeric(&[1.0, 2.0], 0.5).unwrap();
+
+            assert_relative_eq!(jacobian.row(0), [1.0, 2.0].as_slice());
+            assert_relative_eq!(jacobian.get(1, 0), 0.0);
+            assert_relative_eq!(jacobian.get(1, 1), -1.0);
+        }
+    }
+
+    mod saliency {
+        use super::*;
+
+        #[test]
+        fn test() {
+            // y0 = x0 + 2 * x1, y1 = -x1
+            let network = Network::from_weights(
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(2).with_activation(Activation::Identity),
+                ],
+                vec![0.0, 1.0, 2.0, 0.5, 0.0, -1.0],
+            );
+
+            let saliency = network.saliency(&[1.0, 2.0, -3.0, 0.5]);
+
+            assert_relative_eq!(saliency.as_slice(), [1.0, 3.0].as_slice());
+        }
+
+        #[test]
+        fn ignores_unused_inputs() {
+            // The second input is not connected to anything
+            let network = Network::from_weights(
+                &[
+                    LayerTopology::new(3),
+                    LayerTopology::new(3).with_activation(Activation::Identity),
+                    LayerTopology::new(3).with_kind(LayerKind::Softmax),
+                ],
+                [
+                    [0.0, 1.0, 0.0, 0.0],
+                    [0.0, 0.0, 0.0, 2.0],
+                    [0.0, 0.0, 0.0, 0.0],
+                ]
+                .concat(),
+            );
+
+            let saliency = network.saliency(&[0.1, 0.2, 0.3, -0.5, 1.0, 0.0]);
+
+            assert!(saliency[0] > 0.0);
+            assert_relative_eq!(saliency[1], 0.0);
+            assert!(saliency[2] > 0.0);
+        }
+
+        #[test]
+        fn falls_back_to_finite_differences() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(1).with_kind(LayerKind::Lstm),
+                ],
+            );
+
+            let jacobian = network
+                .jacobian_numeric(&[0.5, -0.5], SALIENCY_STEP)
+                .unwrap();
+            let expected: Vec<_> = jacobian.row(0).iter().map(|value| value.abs()).collect();
+
+            let saliency = network.saliency(&[0.5, -0.5]);
+
+            assert_relative_eq!(saliency.as_slice(), expected.as_slice());
+        }
+    }
+}
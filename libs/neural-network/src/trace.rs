@@ -0,0 +1,269 @@
+use crate::*;
+
+/// Values of a single layer's neurons, as seen during `propagate_traced`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerTrace {
+    /// Values before the activation - see `Layer::propagate_traced`.
+    pub sums: Vec<f32>,
+    pub outputs: Vec<f32>,
+}
+
+/// Everything that happened during a single `propagate_traced` call.
+///
+/// Layers are listed without the input layer, i.e. `layers[0]` is the first
+/// hidden layer and the last one holds the network's outputs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkTrace {
+    pub layers: Vec<LayerTrace>,
+}
+
+impl NetworkTrace {
+    pub fn outputs(&self) -> &[f32] {
+        &self.layers[self.layers.len() - 1].outputs
+    }
+}
+
+impl Network {
+    /// Like `propagate`, but also returns every layer's values before and
+    /// after the activation.
+    pub fn propagate_traced(&mut self, inputs: &[f32]) -> NetworkTrace {
+        let mut layers: Vec<LayerTrace> = Vec::with_capacity(self.layers.len());
+
+        for layer in &mut self.layers {
+            let mut trace = LayerTrace {
+                sums: vec![0.0; layer.output_size()],
+                outputs: vec![0.0; layer.output_size()],
+            };
+
+            let inputs = layers.last().map_or(inputs, |prev| &prev.outputs);
+
+            layer.propagate_traced(inputs, &mut trace.sums, &mut trace.outputs);
+            layers.push(trace);
+        }
+
+        NetworkTrace { layers }
+    }
+}
+
+/// Per-neuron statistics, aggregated over many traces.
+///
+/// A neuron counts as dead for given call when its output is exactly zero,
+/// which is what a ReLU stuck below zero yields.
+#[derive(Clone, Debug, Default)]
+pub struct ActivationStats {
+    calls: usize,
+    layers: Vec<NeuronTotals>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct NeuronTotals {
+    outputs: Vec<f64>,
+    dead: Vec<usize>,
+    min: Vec<f32>,
+    max: Vec<f32>,
+}
+
+/// See `ActivationStats`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NeuronStats {
+    pub mean: f32,
+    pub min: f32,
+    pub max: f32,
+    /// Fraction of calls for which the neuron was dead.
+    pub dead: f32,
+}
+
+impl ActivationStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, trace: &NetworkTrace) {
+        if self.layers.is_empty() {
+            self.layers = trace
+                .layers
+                .iter()
+                .map(|layer| NeuronTotals {
+                    outputs: vec![0.0; layer.outputs.len()],
+                    dead: vec![0; layer.outputs.len()],
+                    min: vec![f32::INFINITY; layer.outputs.len()],
+                    max: vec![f32::NEG_INFINITY; layer.outputs.len()],
+                })
+                .collect();
+        }
+
+        assert_eq!(self.layers.len(), trace.layers.len());
+
+        for (totals, layer) in self.layers.iter_mut().zip(&trace.layers) {
+            assert_eq!(totals.outputs.len(), layer.outputs.len());
+
+            for (neuron, &output) in layer.outputs.iter().enumerate() {
+                totals.outputs[neuron] += output as f64;
+                totals.min[neuron] = totals.min[neuron].min(output);
+                totals.max[neuron] = totals.max[neuron].max(output);
+
+                if output == 0.0 {
+                    totals.dead[neuron] += 1;
+                }
+            }
+        }
+
+        self.calls += 1;
+    }
+
+    /// Number of recorded traces.
+    pub fn calls(&self) -> usize {
+        self.calls
+    }
+
+    /// Layers are numbered as in `NetworkTrace`.
+    pub fn neuron(&self, layer: usize, neuron: usize) -> NeuronStats {
+        let totals = &self.layers[layer];
+        let calls = self.calls.max(1) as f64;
+
+        NeuronStats {
+            mean: (totals.outputs[neuron] / calls) as f32,
+            min: totals.min[neuron],
+            max: totals.max[neuron],
+            dead: (totals.dead[neuron] as f64 / calls) as f32,
+        }
+    }
+
+    pub fn layer(&self, layer: usize) -> impl Iterator<Item = NeuronStats> + '_ {
+        (0..self.layers[layer].outputs.len()).map(move |neuron| self.neuron(layer, neuron))
+    }
+
+    /// Returns `(layer, neuron)` of neurons that were dead for at least
+    /// `threshold` (fraction) of the calls.
+    pub fn dead_neurons(&self, threshold: f32) -> Vec<(usize, usize)> {
+        (0..self.layers.len())
+            .flat_map(|layer| {
+                self.layer(layer)
+                    .enumerate()
+                    .filter(move |(_, stats)| self.calls > 0 && stats.dead >= threshold)
+                    .map(move |(neuron, _)| (layer, neuron))
+            })
+            .collect()
+    }
+
+    /// Whether the recorded traces fit `network`'s layers.
+    pub(crate) fn matches(&self, network: &Network) -> bool {
+        self.layers.len() == network.layers.len()
+            && self
+                .layers
+                .iter()
+                .zip(&network.layers)
+                .all(|(totals, layer)| totals.outputs.len() == layer.output_size())
+    }
+
+    /// Forgets all the recorded traces.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn network() -> Network {
+        Network::new(vec![
+            Layer::Dense(DenseLayer::new(
+                vec![0.0, -10.0],
+                vec![1.0, 1.0, 1.0, 1.0],
+                Activation::ReLU,
+            )),
+            Layer::Recurrent(RecurrentLayer::new(
+                vec![0.5],
+                vec![1.0, -1.0],
+                vec![0.5],
+                Activation::Tanh,
+            )),
+        ])
+    }
+
+    mod propagate_traced {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut network = network();
+            let trace = network.propagate_traced(&[1.0, -3.0]);
+
+            assert_relative_eq!(trace.layers[0].sums.as_slice(), [-2.0, -12.0].as_slice());
+            assert_relative_eq!(trace.layers[0].outputs.as_slice(), [0.0, 0.0].as_slice());
+            assert_relative_eq!(trace.layers[1].sums.as_slice(), [0.5].as_slice());
+            assert_relative_eq!(trace.outputs()[0], 0.5f32.tanh());
+        }
+
+        #[test]
+        fn matches_propagate() {
+            let mut network = network();
+            let mut reference = network.clone();
+
+            for inputs in [[1.0, 2.0], [0.5, -0.5], [3.0, 1.0]] {
+                let expected = reference.propagate(inputs.to_vec());
+                let actual = network.propagate_traced(&inputs);
+
+                assert_relative_eq!(actual.outputs(), expected.as_slice());
+            }
+        }
+
+        #[test]
+        fn gated() {
+            let layers = &[
+                LayerTopology::new(2),
+                LayerTopology::new(2).with_kind(LayerKind::Lstm),
+                LayerTopology::new(1).with_kind(LayerKind::Gru),
+            ];
+
+            let weights = (0..52).map(|n| n as f32 / 100.0 - 0.25);
+            let mut network = Network::from_weights(layers, weights);
+            let mut reference = network.clone();
+
+            for inputs in [[1.0, 2.0], [0.5, -0.5]] {
+                let expected = reference.propagate(inputs.to_vec());
+                let actual = network.propagate_traced(&inputs);
+
+                assert_relative_eq!(actual.outputs(), expected.as_slice());
+            }
+        }
+    }
+
+    mod activation_stats {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut network = network();
+            let mut stats = ActivationStats::new();
+
+            for inputs in [[1.0, 2.0], [-1.0, -2.0], [2.0, 2.0], [3.0, 10.0]] {
+                stats.record(&network.propagate_traced(&inputs));
+            }
+
+            assert_eq!(stats.calls(), 4);
+
+            let first = stats.neuron(0, 0);
+
+            assert_relative_eq!(first.mean, (3.0 + 4.0 + 13.0) / 4.0);
+            assert_relative_eq!(first.dead, 0.25);
+            assert_relative_eq!(first.min, 0.0);
+            assert_relative_eq!(first.max, 13.0);
+
+            let second = stats.neuron(0, 1);
+
+            assert_relative_eq!(second.mean, 3.0 / 4.0);
+            assert_relative_eq!(second.dead, 0.75);
+
+            assert_eq!(stats.dead_neurons(0.5), vec![(0, 1)]);
+            assert_eq!(stats.layer(0).count(), 2);
+
+            stats.reset();
+
+            assert_eq!(stats.calls(), 0);
+            assert!(stats.dead_neurons(0.0).is_empty());
+        }
+    }
+}
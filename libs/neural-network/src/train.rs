@@ -0,0 +1,381 @@
+//! Supervised training with backpropagation.
+//!
+//! Supports dense, recurrent, softmax and layer normalization layers
+//! (through all the activations); the recurrent ones are trained from their
+//! current state, which is treated as a constant input - i.e. gradients
+//! don't flow back through time.
+
+pub use self::{loss::*, optimizer::*};
+
+use crate::*;
+
+mod loss;
+mod optimizer;
+
+/// Trains networks using gradient descent.
+pub struct Trainer {
+    loss: Loss,
+    optimizer: Box<dyn Optimizer>,
+}
+
+impl Trainer {
+    pub fn new(loss: Loss, optimizer: impl Optimizer + 'static) -> Self {
+        Self {
+            loss,
+            optimizer: Box::new(optimizer),
+        }
+    }
+
+    /// Performs a single optimization step on a batch of samples, laid out
+    /// as in `Network::propagate_batch`; returns the loss from before the
+    /// step.
+    ///
+    /// Optimizers keep per-weight state, so a trainer should be used for a
+    /// single network only.
+    pub fn train_batch(
+        &mut self,
+        network: &mut Network,
+        inputs: &[f32],
+        targets: &[f32],
+    ) -> Result<f32, Error> {
+        let (loss, gradients) = network.gradients(inputs, targets, self.loss)?;
+        let mut weights: Vec<_> = network.weights().collect();
+
+        self.optimizer.step(&mut weights, &gradients);
+
+        for (weight, new_weight) in network.weights_mut().zip(weights) {
+            *weight = new_weight;
+        }
+
+        Ok(loss)
+    }
+}
+
+impl Network {
+    /// Computes the loss on a batch of samples (laid out as in
+    /// `propagate_batch`) and its gradient with respect to every weight,
+    /// ordered as in `weights`; both are averaged over the samples.
+    ///
+    /// Fails for networks with layers other than dense, recurrent, softmax
+    /// and layer normalization ones.
+    pub fn gradients(
+        &self,
+        inputs: &[f32],
+        targets: &[f32],
+        loss: Loss,
+    ) -> Result<(f32, Vec<f32>), Error> {
+        self.check_backpropagation()?;
+
+        let input_size = self.layers[0].input_size();
+        let output_size = self.layers[self.layers.len() - 1].output_size();
+
+        assert_eq!(inputs.len() % input_size, 0);
+
+        let batch_size = inputs.len() / input_size;
+
+        assert!(batch_size > 0);
+        assert_eq!(targets.len(), batch_size * output_size);
+
+        let offsets: Vec<_> = std::iter::once(0)
+            .chain(self.layers.iter().scan(0, |offset, layer| {
+                *offset += layer.weight_count();
+                Some(*offset)
+            }))
+            .collect();
+
+        let mut gradients = vec![0.0; offsets[self.layers.len()]];
+        let mut total_loss = 0.0;
+
+        // activations[0] holds the inputs, activations[n + 1] - outputs of
+        // the n-th layer
+        let mut activations: Vec<_> = std::iter::once(input_size)
+            .chain(self.layers.iter().map(Layer::output_size))
+            .map(|size| vec![0.0; size])
+            .collect();
+
+        let mut sums: Vec<_> = self
+            .layers
+            .iter()
+            .map(|layer| vec![0.0; layer.output_size()])
+            .collect();
+
+        for (inputs, targets) in inputs
+            .chunks_exact(input_size)
+            .zip(targets.chunks_exact(output_size))
+        {
+            activations[0].copy_from_slice(inputs);
+
+            for (idx, layer) in self.layers.iter().enumerate() {
+                let (inputs, outputs) = activations.split_at_mut(idx + 1);
+                layer.forward_train(&inputs[idx], &mut sums[idx], &mut outputs[0]);
+            }
+
+            let outputs = &activations[self.layers.len()];
+            let mut deltas = vec![0.0; output_size];
+
+            total_loss += loss.loss(outputs, targets);
+            loss.gradient(outputs, targets, &mut deltas);
+
+            for (idx, layer) in self.layers.iter().enumerate().rev() {
+                let mut input_gradients = vec![0.0; layer.input_size()];
+
+                layer.backward(
+                    &activations[idx],
+                    &sums[idx],
+                    &mut deltas,
+                    &mut input_gradients,
+                    &mut gradients[offsets[idx]..offsets[idx + 1]],
+                );
+
+                deltas = input_gradients;
+            }
+        }
+
+        for gradient in &mut gradients {
+            *gradient /= batch_size as f32;
+        }
+
+        Ok((total_loss / batch_size as f32, gradients))
+    }
+
+    /// Checks that all the layers support backpropagation.
+    pub(crate) fn check_backpropagation(&self) -> Result<(), Error> {
+        for (idx, layer) in self.layers.iter().enumerate() {
+            let kind = layer.kind();
+
+            if !kind.supports_backpropagation() {
+                return Err(Error::UnsupportedLayer {
+                    layer: idx + 1,
+                    kind,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use layer_topology::LayerKind;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn topology() -> [LayerTopology; 3] {
+        [
+            LayerTopology::new(2),
+            LayerTopology::new(3)
+                .with_activation(Activation::Tanh)
+                .with_kind(LayerKind::Recurrent),
+            LayerTopology::new(2).with_activation(Activation::Sigmoid),
+        ]
+    }
+
+    mod gradients {
+        use super::*;
+
+        fn assert_match_finite_differences(
+            network: &Network,
+            inputs: &[f32],
+            targets: &[f32],
+            loss: Loss,
+        ) {
+            let (_, actual) = network.gradients(inputs, targets, loss).unwrap();
+            let weights: Vec<_> = network.weights().collect();
+
+            let loss_at = |weights: &[f32]| {
+                let mut network = network.clone();
+
+                for (weight, new_weight) in network.weights_mut().zip(weights) {
+                    *weight = *new_weight;
+                }
+
+                network.gradients(inputs, targets, loss).unwrap().0
+            };
+
+            for idx in 0..weights.len() {
+                let h = 1e-2;
+                let mut plus = weights.clone();
+                let mut minus = weights.clone();
+
+                plus[idx] += h;
+                minus[idx] -= h;
+
+                let expected = (loss_at(&plus) - loss_at(&minus)) / (2.0 * h);
+
+                assert_relative_eq!(actual[idx], expected, epsilon = 1e-3);
+            }
+        }
+
+        #[test]
+        fn match_finite_differences() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut network = Network::random(&mut rng, &topology());
+
+            // Give the recurrent layer some state to differentiate from
+            network.propagate(vec![0.5, -0.5]);
+
+            let inputs = [0.3, -0.8, 1.0, 0.2];
+            let targets = [0.0, 1.0, 1.0, 0.5];
+
+            for loss in [Loss::MeanSquaredError, Loss::CrossEntropy] {
+                assert_match_finite_differences(&network, &inputs, &targets, loss);
+            }
+        }
+
+        #[test]
+        fn flow_through_layer_norm_and_softmax() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(3).with_activation(Activation::Tanh),
+                    LayerTopology::new(3)
+                        .with_activation(Activation::Tanh)
+                        .with_kind(LayerKind::LayerNorm),
+                    LayerTopology::new(3).with_kind(LayerKind::Softmax),
+                ],
+            );
+
+            let inputs = [0.3, -0.8, 1.0, 0.2];
+            let targets = [0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+
+            for loss in [
+                Loss::MeanSquaredError,
+                Loss::CrossEntropy,
+                Loss::CategoricalCrossEntropy,
+            ] {
+                assert_match_finite_differences(&network, &inputs, &targets, loss);
+            }
+        }
+
+        #[test]
+        fn rejects_unsupported_layers() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(2).with_kind(LayerKind::Gru),
+                ],
+            );
+
+            assert_eq!(
+                network
+                    .gradients(&[0.0, 0.0], &[0.0, 1.0], Loss::MeanSquaredError)
+                    .unwrap_err(),
+                Error::UnsupportedLayer {
+                    layer: 1,
+                    kind: LayerKind::Gru
+                }
+            );
+        }
+    }
+
+    mod train_batch {
+        use super::*;
+
+        fn train(optimizer: impl Optimizer + 'static) -> (f32, f32) {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let mut network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(4).with_activation(Activation::Tanh),
+                    LayerTopology::new(1).with_activation(Activation::Sigmoid),
+                ],
+            );
+
+            let mut trainer = Trainer::new(Loss::CrossEntropy, optimizer);
+
+            // XOR
+            let inputs = [0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0];
+            let targets = [0.0, 1.0, 1.0, 0.0];
+
+            let first = trainer
+                .train_batch(&mut network, &inputs, &targets)
+                .unwrap();
+
+            for _ in 0..2000 {
+                trainer
+                    .train_batch(&mut network, &inputs, &targets)
+                    .unwrap();
+            }
+
+            let last = network
+                .gradients(&inputs, &targets, Loss::CrossEntropy)
+                .unwrap()
+                .0;
+
+            (first, last)
+        }
+
+        #[test]
+        fn sgd() {
+            let (first, last) = train(Sgd::new(0.5).with_momentum(0.9));
+
+            assert!(first > 0.5);
+            assert!(last < 0.05);
+        }
+
+        #[test]
+        fn softmax_classifier() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let mut network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(8).with_activation(Activation::Tanh),
+                    LayerTopology::new(3).with_activation(Activation::Identity),
+                    LayerTopology::new(3).with_kind(LayerKind::Softmax),
+                ],
+            );
+
+            let mut trainer = Trainer::new(Loss::CategoricalCrossEntropy, Adam::new(0.05));
+
+            // Which of the three points each input is closest to
+            let inputs = [0.0, 1.0, -1.0, -0.5, 1.0, -0.5, 0.1, 0.8, -0.8, -0.4];
+            let targets = [
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+            ]
+            .concat();
+
+            let first = trainer
+                .train_batch(&mut network, &inputs, &targets)
+                .unwrap();
+
+            for _ in 0..500 {
+                trainer
+                    .train_batch(&mut network, &inputs, &targets)
+                    .unwrap();
+            }
+
+            let last = network
+                .gradients(&inputs, &targets, Loss::CategoricalCrossEntropy)
+                .unwrap()
+                .0;
+
+            assert!(first > 0.5);
+            assert!(last < 0.05);
+        }
+
+        #[test]
+        fn adam() {
+            let (first, last) = train(Adam::new(0.05));
+
+            assert!(first > 0.5);
+            assert!(last < 0.05);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+/// Function measuring how far network's outputs are from the targets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Loss {
+    MeanSquaredError,
+    /// Binary cross-entropy, for outputs within `(0, 1)` - e.g. coming from
+    /// sigmoid.
+    CrossEntropy,
+    /// Cross-entropy between two probability distributions - e.g. softmax
+    /// outputs and one-hot targets.
+    CategoricalCrossEntropy,
+}
+
+impl Loss {
+    /// Outputs are clamped this far from 0.0 and 1.0 when computing the
+    /// cross-entropy, so that it stays finite.
+    const EPSILON: f32 = 1e-7;
+
+    /// Loss averaged over the outputs - except for the categorical
+    /// cross-entropy, which (comparing whole distributions) sums them up.
+    pub fn loss(self, outputs: &[f32], targets: &[f32]) -> f32 {
+        assert_eq!(outputs.len(), targets.len());
+
+        if self == Self::CategoricalCrossEntropy {
+            return outputs
+                .iter()
+                .zip(targets)
+                .map(|(&output, &target)| -target * libm::logf(output.max(Self::EPSILON)))
+                .sum();
+        }
+
+        let sum: f32 = outputs
+            .iter()
+            .zip(targets)
+            .map(|(&output, &target)| match self {
+                Self::MeanSquaredError => (output - target).powi(2),
+                Self::CrossEntropy => {
+                    let output = output.clamp(Self::EPSILON, 1.0 - Self::EPSILON);
+
+                    -(target * libm::logf(output) + (1.0 - target) * libm::logf(1.0 - output))
+                }
+                Self::CategoricalCrossEntropy => unreachable!(),
+            })
+            .sum();
+
+        sum / outputs.len() as f32
+    }
+
+    /// Writes the gradient of `loss` with respect to the outputs.
+    pub fn gradient(self, outputs: &[f32], targets: &[f32], gradients: &mut [f32]) {
+        assert_eq!(outputs.len(), targets.len());
+        assert_eq!(outputs.len(), gradients.len());
+
+        let len = outputs.len() as f32;
+
+        for ((gradient, &output), &target) in gradients.iter_mut().zip(outputs).zip(targets) {
+            *gradient = match self {
+                Self::MeanSquaredError => 2.0 * (output - target) / len,
+                Self::CrossEntropy => {
+                    let output = output.clamp(Self::EPSILON, 1.0 - Self::EPSILON);
+
+                    (output - target) / (output * (1.0 - output)) / len
+                }
+                Self::CategoricalCrossEntropy => -target / output.max(Self::EPSILON),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn mean_squared_error() {
+        let mut gradients = [0.0; 2];
+
+        Loss::MeanSquaredError.gradient(&[0.5, 2.0], &[1.0, 1.0], &mut gradients);
+
+        assert_relative_eq!(
+            Loss::MeanSquaredError.loss(&[0.5, 2.0], &[1.0, 1.0]),
+            (0.25 + 1.0) / 2.0
+        );
+
+        assert_relative_eq!(gradients.as_slice(), [-0.5, 1.0].as_slice());
+    }
+
+    #[test]
+    fn cross_entropy() {
+        let mut gradients = [0.0; 2];
+
+        Loss::CrossEntropy.gradient(&[0.25, 0.5], &[1.0, 0.0], &mut gradients);
+
+        assert_relative_eq!(
+            Loss::CrossEntropy.loss(&[0.25, 0.5], &[1.0, 0.0]),
+            -(0.25f32.ln() + 0.5f32.ln()) / 2.0
+        );
+
+        assert_relative_eq!(gradients.as_slice(), [-2.0, 1.0].as_slice());
+        assert!(Loss::CrossEntropy.loss(&[0.0], &[1.0]).is_finite());
+    }
+
+    #[test]
+    fn categorical_cross_entropy() {
+        let loss = Loss::CategoricalCrossEntropy;
+        let mut gradients = [0.0; 3];
+
+        loss.gradient(&[0.25, 0.5, 0.25], &[0.0, 1.0, 0.0], &mut gradients);
+
+        assert_relative_eq!(
+            loss.loss(&[0.25, 0.5, 0.25], &[0.0, 1.0, 0.0]),
+            -0.5f32.ln()
+        );
+
+        assert_relative_eq!(gradients.as_slice(), [0.0, -2.0, 0.0].as_slice());
+        assert!(loss.loss(&[1.0, 0.0], &[0.0, 1.0]).is_finite());
+    }
+}
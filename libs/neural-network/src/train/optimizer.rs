@@ -0,0 +1,9 @@
+pub use self::{adam::*, sgd::*};
+
+mod adam;
+mod sgd;
+
+pub trait Optimizer {
+    /// Updates `weights` given the loss' gradient with respect to them.
+    fn step(&mut self, weights: &mut [f32], gradients: &[f32]);
+}
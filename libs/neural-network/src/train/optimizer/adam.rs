@@ -0,0 +1,85 @@
+use crate::train::*;
+
+/// Adam optimizer (Kingma & Ba, 2014).
+pub struct Adam {
+    learning_rate: f32,
+    beta1: f32,
+    beta2: f32,
+    epsilon: f32,
+    steps: i32,
+    means: Vec<f32>,
+    variances: Vec<f32>,
+}
+
+impl Adam {
+    pub fn new(learning_rate: f32) -> Self {
+        assert!(learning_rate > 0.0);
+
+        Self {
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+            steps: 0,
+            means: Vec::new(),
+            variances: Vec::new(),
+        }
+    }
+
+    /// Decay rates of the gradient's running mean and variance.
+    pub fn with_betas(mut self, beta1: f32, beta2: f32) -> Self {
+        assert!((0.0..1.0).contains(&beta1));
+        assert!((0.0..1.0).contains(&beta2));
+
+        self.beta1 = beta1;
+        self.beta2 = beta2;
+        self
+    }
+}
+
+impl Optimizer for Adam {
+    fn step(&mut self, weights: &mut [f32], gradients: &[f32]) {
+        assert_eq!(weights.len(), gradients.len());
+
+        self.steps += 1;
+        self.means.resize(weights.len(), 0.0);
+        self.variances.resize(weights.len(), 0.0);
+
+        let mean_correction = 1.0 - self.beta1.powi(self.steps);
+        let variance_correction = 1.0 - self.beta2.powi(self.steps);
+
+        for (((weight, mean), variance), &gradient) in weights
+            .iter_mut()
+            .zip(&mut self.means)
+            .zip(&mut self.variances)
+            .zip(gradients)
+        {
+            *mean = self.beta1 * *mean + (1.0 - self.beta1) * gradient;
+            *variance = self.beta2 * *variance + (1.0 - self.beta2) * gradient * gradient;
+
+            let mean = *mean / mean_correction;
+            let variance = *variance / variance_correction;
+
+            *weight -= self.learning_rate * mean / (variance.sqrt() + self.epsilon);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn first_step_follows_gradients_sign() {
+        let mut weights = [1.0, 1.0, 1.0];
+
+        Adam::new(0.1).step(&mut weights, &[5.0, -0.01, 0.0]);
+
+        assert_relative_eq!(
+            weights.as_slice(),
+            [0.9, 1.1, 1.0].as_slice(),
+            epsilon = 1e-4
+        );
+    }
+}
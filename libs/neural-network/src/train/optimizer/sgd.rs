@@ -0,0 +1,68 @@
+use crate::train::*;
+
+/// Stochastic gradient descent, optionally with momentum.
+pub struct Sgd {
+    learning_rate: f32,
+    momentum: f32,
+    velocities: Vec<f32>,
+}
+
+impl Sgd {
+    pub fn new(learning_rate: f32) -> Self {
+        assert!(learning_rate > 0.0);
+
+        Self {
+            learning_rate,
+            momentum: 0.0,
+            velocities: Vec::new(),
+        }
+    }
+
+    pub fn with_momentum(mut self, momentum: f32) -> Self {
+        assert!((0.0..1.0).contains(&momentum));
+
+        self.momentum = momentum;
+        self
+    }
+}
+
+impl Optimizer for Sgd {
+    fn step(&mut self, weights: &mut [f32], gradients: &[f32]) {
+        assert_eq!(weights.len(), gradients.len());
+
+        self.velocities.resize(weights.len(), 0.0);
+
+        for ((weight, velocity), gradient) in
+            weights.iter_mut().zip(&mut self.velocities).zip(gradients)
+        {
+            *velocity = self.momentum * *velocity - self.learning_rate * gradient;
+            *weight += *velocity;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test() {
+        let mut weights = [1.0, -1.0];
+
+        Sgd::new(0.1).step(&mut weights, &[2.0, -0.5]);
+
+        assert_relative_eq!(weights.as_slice(), [0.8, -0.95].as_slice());
+    }
+
+    #[test]
+    fn momentum() {
+        let mut weights = [1.0];
+        let mut sgd = Sgd::new(0.1).with_momentum(0.5);
+
+        sgd.step(&mut weights, &[1.0]);
+        sgd.step(&mut weights, &[1.0]);
+
+        assert_relative_eq!(weights[0], 1.0 - 0.1 - (0.5 * 0.1 + 0.1));
+    }
+}
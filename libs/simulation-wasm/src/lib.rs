@@ -4,20 +4,27 @@ use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 pub struct Simulation {
-    rng: ThreadRng,
     sim: simulation::Simulation,
 }
 
 #[wasm_bindgen]
 impl Simulation {
+    /// Without a seed a random one is picked; either way it's available
+    /// through `seed()`, so that the session can be reproduced natively via
+    /// `simulation::Simulation::from_seed`.
     #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        let mut rng = thread_rng();
-        let sim = simulation::Simulation::random(&mut rng);
+    pub fn new(seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(|| thread_rng().gen());
+        let sim = simulation::Simulation::from_seed(seed);
 
-        Self { rng, sim }
+        Self { sim }
     }
 
+    pub fn seed(&self) -> u64 {
+        self.sim.seed()
+    }
+
+    #[allow(deprecated)]
     pub fn world(&self) -> JsValue {
         let world = World::from(self.sim.world());
         //Need to work on the deprecated feature
@@ -25,11 +32,11 @@ impl Simulation {
     }
 
     pub fn step(&mut self) {
-        self.sim.step(&mut self.rng);
+        self.sim.step();
     }
 
     pub fn train(&mut self) -> String {
-        let stats = self.sim.train(&mut self.rng);
+        let stats = self.sim.train();
 
         format!(
             "min={:.2}, max={:.2}, avg={:.2}",
@@ -40,6 +47,12 @@ impl Simulation {
     }
 }
 
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct World {
     pub animals: Vec<Animal>,
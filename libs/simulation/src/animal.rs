@@ -1,5 +1,3 @@
-use genetic_algorithm::Chromosome;
-
 use crate::*;
 
 pub struct Animal {
@@ -12,14 +10,14 @@ pub struct Animal {
 }
 
 impl Animal {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let eye = Eye::default();
+    pub fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        let eye = Eye::from_config(config);
 
-        let brain = Brain::random(rng, &eye);
+        let brain = Brain::random(rng, config);
 
         Self {
-            position: rng.gen(),
-            rotation: rng.gen(),
+            position: config.random_position(rng),
+            rotation: math::random_rotation(rng),
             speed: 0.002,
             eye,
             brain,
@@ -39,22 +37,31 @@ impl Animal {
         self.rotation
     }
 
+    pub fn eye(&self) -> &Eye {
+        &self.eye
+    }
+
+    pub fn brain(&self) -> &Brain {
+        &self.brain
+    }
+
     pub(crate) fn from_chromosome(
+        config: &Config,
         chromosome: genetic_algorithm::Chromosome,
         rng: &mut dyn RngCore,
     ) -> Self {
-        let eye = Eye::default();
-        let brain: Brain = Brain::from_chromosome(chromosome, &eye);
+        let eye = Eye::from_config(config);
+        let brain: Brain = Brain::from_chromosome(chromosome, config);
 
-        Self::new(eye, brain, rng)
+        Self::new(config, eye, brain, rng)
     }
 }
 
 impl Animal {
-    fn new(eye: Eye, brain: Brain, rng: &mut dyn RngCore) -> Self {
+    fn new(config: &Config, eye: Eye, brain: Brain, rng: &mut dyn RngCore) -> Self {
         Self {
-            position: rng.gen(),
-            rotation: rng.gen(),
+            position: config.random_position(rng),
+            rotation: math::random_rotation(rng),
             speed: 0.002,
             eye,
             brain,
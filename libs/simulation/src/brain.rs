@@ -5,31 +5,58 @@ pub struct Brain {
 }
 
 impl Brain {
-    pub fn random(rng: &mut dyn RngCore, eye: &Eye) -> Self {
+    pub fn random(rng: &mut dyn RngCore, config: &Config) -> Self {
         Self {
-            nn: neural_network::Network::random(rng, &Self::topology(eye)),
+            nn: neural_network::Network::random(rng, &Self::topology(config)),
         }
     }
 
-    pub(crate) fn from_chromosome(chromosome: genetic_algorithm::Chromosome, eye: &Eye) -> Self {
+    pub fn from_chromosome(chromosome: genetic_algorithm::Chromosome, config: &Config) -> Self {
         Self {
-            nn: neural_network::Network::from_weights(&Self::topology(eye), chromosome),
+            nn: neural_network::Network::from_weights(&Self::topology(config), chromosome),
         }
     }
 
-    pub(crate) fn as_chromosome(&self) -> genetic_algorithm::Chromosome {
+    pub fn as_chromosome(&self) -> genetic_algorithm::Chromosome {
         self.nn.weights().collect()
     }
 
-    fn topology(eye: &Eye) -> [neural_network::layer_topology::LayerTopology; 3] {
-        [
-            neural_network::layer_topology::LayerTopology {
-                neurons: eye.cells(),
-            },
-            neural_network::layer_topology::LayerTopology {
-                neurons: 2 * eye.cells(),
-            },
-            neural_network::layer_topology::LayerTopology { neurons: 2 },
-        ]
+    /// What changed between this brain and `other` (e.g. its mutated
+    /// offspring); see `neural_network::Network::diff`.
+    pub fn diff(&self, other: &Self, tolerance: f32) -> neural_network::NetworkDiff {
+        self.nn
+            .diff(&other.nn, tolerance)
+            .expect("brains always share the topology")
+    }
+
+    /// Euclidean distance between both brains' weights.
+    pub fn distance(&self, other: &Self) -> f32 {
+        self.nn
+            .l2_distance(&other.nn)
+            .expect("brains always share the topology")
+    }
+
+    /// How much the brain relies on each of the eye's cells, given recorded
+    /// visions (one after another); see `neural_network::Network::saliency`.
+    pub fn saliency(&self, visions: &[f32]) -> Vec<f32> {
+        self.nn.saliency(visions)
+    }
+
+    /// Every layer's values for given vision, starting from the brain's
+    /// current (recurrent) state, which is left intact - e.g. for drawing
+    /// the brain or recording `neural_network::ActivationStats`.
+    pub fn trace(&self, vision: &[f32]) -> neural_network::NetworkTrace {
+        self.nn.clone().propagate_traced(vision)
+    }
+
+    /// Eye's cells, then `config.brain_hidden` and then the outputs.
+    pub(crate) fn topology(config: &Config) -> Vec<neural_network::layer_topology::LayerTopology> {
+        use neural_network::{activation::Activation, layer_topology::LayerTopology};
+
+        std::iter::once(LayerTopology::new(config.eye_cells))
+            .chain(config.brain_hidden.iter().copied())
+            // Speed and rotation both have to be able to go negative
+            .chain([LayerTopology::new(2).with_activation(Activation::Tanh)])
+            .collect()
     }
 }
@@ -0,0 +1,147 @@
+use std::f32::consts::*;
+
+use neural_network::{
+    initializer::Initializer,
+    layer_topology::{LayerKind, LayerTopology},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// Every tunable parameter of a simulation - see `Simulation::from_config`
+/// and, for loading it from files, the `scenario` module.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// How far animals see.
+    pub eye_fov_range: f32,
+    /// How wide animals see, in radians.
+    pub eye_fov_angle: f32,
+    /// Number of photoreceptors, i.e. the brain's inputs.
+    pub eye_cells: usize,
+    /// Maximum amount of uniform noise added to each cell (zero disables
+    /// it).
+    pub eye_noise: f32,
+
+    /// Layers between the eye's cells and the brain's two outputs (speed
+    /// and rotation).
+    pub brain_hidden: Vec<LayerTopology>,
+
+    /// The world wraps around at its edges.
+    pub world_width: f32,
+    pub world_height: f32,
+    pub world_animals: usize,
+    pub world_foods: usize,
+    /// Distance at which an animal eats food.
+    pub food_size: f32,
+    /// Where food appears - both initially and after being eaten.
+    pub food_placement: FoodPlacement,
+
+    pub ga_selection: Selection,
+    pub ga_crossover: Crossover,
+    pub ga_mutation: Mutation,
+    /// Probability of mutating each gene.
+    pub ga_mut_chance: f32,
+    /// Magnitude of the mutations.
+    pub ga_mut_coeff: f32,
+    pub ga_hall_of_fame_capacity: usize,
+    /// Minimum distance between chromosomes kept in the hall of fame.
+    pub ga_hall_of_fame_distance: f32,
+
+    pub sim_speed_min: f32,
+    pub sim_speed_max: f32,
+    /// Maximum change of speed per step.
+    pub sim_speed_accel: f32,
+    /// Maximum change of rotation per step, in radians.
+    pub sim_rotation_accel: f32,
+    /// Number of steps before the animals get evolved.
+    pub sim_generation_length: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FoodPlacement {
+    /// Anywhere in the world.
+    Uniform,
+    /// Within `radius` of one of `count` spots, picked when the world is
+    /// created.
+    Clusters { count: usize, radius: f32 },
+}
+
+/// See `genetic_algorithm::SelectionMethod`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Selection {
+    RouletteWheel,
+}
+
+/// See `genetic_algorithm::CrossOverMethod`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Crossover {
+    Uniform,
+}
+
+/// See `genetic_algorithm::MutationMethod`; all of them are tuned by
+/// `ga_mut_chance` and `ga_mut_coeff`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mutation {
+    Gaussian,
+}
+
+impl Config {
+    /// Default `brain_hidden` for given number of eye cells - scenario
+    /// files without `brain.hidden` get it for their own `eye.cells`.
+    pub fn default_brain_hidden(eye_cells: usize) -> Vec<LayerTopology> {
+        vec![
+            // Recurrent, so that animals can remember food they've just lost
+            // sight of
+            LayerTopology::new(2 * eye_cells)
+                .with_kind(LayerKind::Recurrent)
+                // Keeps ReLUs from dying as the number of eye cells grows
+                .with_initializer(Initializer::HeUniform),
+        ]
+    }
+
+    /// Uniformly random point of the world.
+    pub(crate) fn random_position(&self, rng: &mut dyn RngCore) -> Point2<f32> {
+        let position: Point2<f32> = rng.gen();
+
+        Point2::new(
+            position.x * self.world_width,
+            position.y * self.world_height,
+        )
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let eye_cells = 9;
+
+        Self {
+            eye_fov_range: 0.25,
+            eye_fov_angle: PI + FRAC_PI_4,
+            eye_cells,
+            eye_noise: 0.0,
+
+            brain_hidden: Self::default_brain_hidden(eye_cells),
+
+            world_width: 1.0,
+            world_height: 1.0,
+            world_animals: 40,
+            world_foods: 40,
+            food_size: 0.01,
+            food_placement: FoodPlacement::Uniform,
+
+            ga_selection: Selection::RouletteWheel,
+            ga_crossover: Crossover::Uniform,
+            ga_mutation: Mutation::Gaussian,
+            ga_mut_chance: 0.01,
+            ga_mut_coeff: 0.3,
+            ga_hall_of_fame_capacity: 10,
+            ga_hall_of_fame_distance: 1.0,
+
+            sim_speed_min: 0.001,
+            sim_speed_max: 0.005,
+            sim_speed_accel: 0.2,
+            sim_rotation_accel: FRAC_PI_2,
+            sim_generation_length: 2500,
+        }
+    }
+}
@@ -2,25 +2,32 @@ use std::f32::consts::*;
 
 use crate::*;
 
-const FOV_RANGE: f32 = 0.25;
-const FOV_ANGLE: f32 = PI + FRAC_PI_4;
-const CELLS: usize = 9;
-
 pub struct Eye {
     fov_range: f32,
     fov_angle: f32,
     cells: usize,
+    noise: f32,
 }
 
 impl Eye {
-    fn new(fov_range: f32, fov_angle: f32, cells: usize) -> Self {
+    fn new(fov_range: f32, fov_angle: f32, cells: usize, noise: f32) -> Self {
         Self {
             fov_angle,
             fov_range,
             cells,
+            noise,
         }
     }
 
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.eye_fov_range,
+            config.eye_fov_angle,
+            config.eye_cells,
+            config.eye_noise,
+        )
+    }
+
     pub fn cells(&self) -> usize {
         self.cells
     }
@@ -30,8 +37,26 @@ impl Eye {
         posiition: nalgebra::Point2<f32>,
         rotation: nalgebra::Rotation2<f32>,
         foods: &[Food],
+        rng: &mut dyn RngCore,
     ) -> Vec<f32> {
         let mut cells = vec![0.0; self.cells()];
+        self.process_vision_into(posiition, rotation, foods, rng, &mut cells);
+        cells
+    }
+
+    /// Like `process_vision`, but writes into `cells` (which must have
+    /// exactly `self.cells()` elements) instead of allocating.
+    pub fn process_vision_into(
+        &self,
+        posiition: nalgebra::Point2<f32>,
+        rotation: nalgebra::Rotation2<f32>,
+        foods: &[Food],
+        rng: &mut dyn RngCore,
+        cells: &mut [f32],
+    ) {
+        assert_eq!(cells.len(), self.cells());
+
+        cells.fill(0.0);
 
         for food in foods {
             let vec = food.position - posiition;
@@ -42,10 +67,10 @@ impl Eye {
                 continue;
             }
 
-            let mut angle =
-                nalgebra::Rotation2::rotation_between(&nalgebra::Vector2::y(), &vec).angle();
+            // Angle between the y axis and `vec`
+            let mut angle = libm::atan2f(-vec.x, vec.y);
 
-            angle = nalgebra::wrap(angle - rotation.angle(), -PI, PI);
+            angle = nalgebra::wrap(angle - math::angle(&rotation), -PI, PI);
 
             if angle.abs() > self.fov_angle / 2.0 {
                 continue;
@@ -61,16 +86,19 @@ impl Eye {
 
             cells[cell] = energy;
         }
-        cells
+
+        // Noise is drawn only when enabled, so that a noiseless eye doesn't
+        // consume the vision stream at all
+        if self.noise > 0.0 {
+            for cell in cells.iter_mut() {
+                *cell = (*cell + rng.gen_range(-self.noise..=self.noise)).clamp(0.0, 1.0);
+            }
+        }
     }
 }
 
 impl Default for Eye {
     fn default() -> Self {
-        Self {
-            fov_range: FOV_RANGE,
-            fov_angle: FOV_ANGLE,
-            cells: CELLS,
-        }
+        Self::from_config(&Config::default())
     }
 }
@@ -1,65 +1,136 @@
-pub use self::{animal::*, brain::*, eye::*, food::*, world::*};
+//! Seeding model
+//!
+//! A whole training run is reproducible from a single `u64` seed (see
+//! `Simulation::from_seed`). The seed is expanded into independent ChaCha8
+//! streams - one for world initialization, vision noise, food respawning
+//! and evolution - so that changes in how one of those consumes randomness
+//! don't shift the others.
+//!
+//! Both `neural_network::Network::random` and
+//! `genetic_algorithm::GeneticAlgorithm::evolve` draw from the given RNG in
+//! a fixed order, so runs are bit-for-bit identical given the same seed and
+//! enabled features - also across targets (e.g. native vs. the wasm32
+//! browser build), since `exp`, `tanh`, `atan2` and the like come from
+//! `libm` rather than the platform's math library.
+
+pub use self::{animal::*, brain::*, config::*, eye::*, food::*, world::*};
 
 mod animal;
 mod animal_individual;
 mod brain;
+mod config;
 mod eye;
 mod food;
+mod math;
+pub mod scenario;
+mod streams;
 mod world;
 
-use self::animal_individual::*;
-use genetic_algorithm::{self, GeneticAlgorithm, Individual};
+use self::{animal_individual::*, streams::*};
+use genetic_algorithm::{self, GeneticAlgorithm};
 use nalgebra::{Point2, Rotation2};
-use neural_network;
 use rand::{Rng, RngCore};
 
-use std::f32::consts::FRAC_PI_2;
-
-const SPEED_MIN: f32 = 0.001;
-const SPEED_MAX: f32 = 0.005;
-const SPEED_ACCEL: f32 = 0.2;
-const ROTATION_ACCEL: f32 = FRAC_PI_2;
-
-const GENERATION_LENGTH: usize = 2500;
-
 pub struct Simulation {
+    config: Config,
     world: World,
     ga: GeneticAlgorithm<genetic_algorithm::RouletteWheelSelection>,
     age: usize,
+    seed: u64,
+    streams: Streams,
+    vision: Vec<f32>,
+    scratch: neural_network::NetworkScratch,
 }
 
 impl Simulation {
     pub fn random(rng: &mut dyn RngCore) -> Self {
-        let world = World::random(rng);
-        let ga = GeneticAlgorithm::new(
-            genetic_algorithm::RouletteWheelSelection,
-            genetic_algorithm::UniformCrossover,
-            genetic_algorithm::GaussianMutation::new(0.01, 0.3),
+        Self::from_seed(rng.gen())
+    }
+
+    pub fn from_seed(seed: u64) -> Self {
+        Self::from_config(Config::default(), seed)
+    }
+
+    /// Panics for invalid configs - see `Config::validate`.
+    pub fn from_config(config: Config, seed: u64) -> Self {
+        Self::try_from_config(config, seed).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_from_config(config: Config, seed: u64) -> Result<Self, scenario::ScenarioError> {
+        config.validate()?;
+
+        let mut streams = Streams::new(seed);
+        let world = World::random(&config, &mut streams.world);
+        let selection = match config.ga_selection {
+            Selection::RouletteWheel => genetic_algorithm::RouletteWheelSelection,
+        };
+
+        let crossover = match config.ga_crossover {
+            Crossover::Uniform => genetic_algorithm::UniformCrossover,
+        };
+
+        let mutation = match config.ga_mutation {
+            Mutation::Gaussian => {
+                genetic_algorithm::GaussianMutation::new(config.ga_mut_chance, config.ga_mut_coeff)
+            }
+        };
+
+        let ga = GeneticAlgorithm::new(selection, crossover, mutation).with_hall_of_fame(
+            genetic_algorithm::HallOfFame::new(
+                config.ga_hall_of_fame_capacity,
+                config.ga_hall_of_fame_distance,
+            ),
         );
-        Self { world, ga, age: 0 }
+
+        Ok(Self {
+            vision: vec![0.0; config.eye_cells],
+            scratch: neural_network::NetworkScratch::new(&Brain::topology(&config)),
+            config,
+            world,
+            ga,
+            age: 0,
+            seed,
+            streams,
+        })
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
     }
 
     pub fn world(&self) -> &World {
         &self.world
     }
 
-    pub fn step(&mut self, rng: &mut dyn RngCore) -> Option<genetic_algorithm::Statistics> {
-        self.process_collisions(rng);
+    /// Best brains (as chromosomes, see `Brain::from_chromosome`) across all
+    /// the generations evolved so far.
+    pub fn hall_of_fame(&self) -> &genetic_algorithm::HallOfFame {
+        self.ga
+            .hall_of_fame()
+            .expect("simulation always keeps a hall of fame")
+    }
+
+    pub fn step(&mut self) -> Option<genetic_algorithm::Statistics> {
+        self.process_collisions();
         self.process_brains();
         self.process_movements();
 
         self.age += 1;
 
-        if self.age > GENERATION_LENGTH {
-            Some(self.evolve(rng))
+        if self.age > self.config.sim_generation_length {
+            Some(self.evolve())
         } else {
             None
         }
     }
 
-    pub fn train(&mut self, rng: &mut dyn RngCore) -> genetic_algorithm::Statistics {
+    pub fn train(&mut self) -> genetic_algorithm::Statistics {
         loop {
-            if let Some(summary) = self.step(rng) {
+            if let Some(summary) = self.step() {
                 return summary;
             }
         }
@@ -69,19 +140,20 @@ impl Simulation {
         for animal in &mut self.world.animals {
             animal.position += animal.rotation * nalgebra::Vector2::new(0.0, animal.speed);
 
-            animal.position.x = nalgebra::wrap(animal.position.x, 0.0, 1.0);
-            animal.position.y = nalgebra::wrap(animal.position.y, 0.0, 1.0);
+            animal.position.x = nalgebra::wrap(animal.position.x, 0.0, self.config.world_width);
+            animal.position.y = nalgebra::wrap(animal.position.y, 0.0, self.config.world_height);
         }
     }
 
-    fn process_collisions(&mut self, rng: &mut dyn RngCore) {
+    fn process_collisions(&mut self) {
         for animal in &mut self.world.animals {
             for food in &mut self.world.foods {
                 let distance = nalgebra::distance(&animal.position, &food.position);
 
-                if distance <= 0.01 {
+                if distance <= self.config.food_size {
                     animal.satiation += 1;
-                    food.position = rng.gen();
+                    food.position =
+                        place_food(&self.config, &self.world.food_spots, &mut self.streams.food);
                 }
             }
         }
@@ -89,42 +161,242 @@ impl Simulation {
 
     fn process_brains(&mut self) {
         for animal in &mut self.world.animals {
-            let vision =
-                animal
-                    .eye
-                    .process_vision(animal.position, animal.rotation, &self.world.foods);
-            let response = animal.brain.nn.propagate(vision);
+            self.vision.resize(animal.eye.cells(), 0.0);
+
+            animal.eye.process_vision_into(
+                animal.position,
+                animal.rotation,
+                &self.world.foods,
+                &mut self.streams.vision,
+                &mut self.vision,
+            );
+
+            let response = animal
+                .brain
+                .nn
+                .propagate_into(&self.vision, &mut self.scratch);
 
-            let speed = response[0].clamp(-SPEED_ACCEL, SPEED_ACCEL);
+            let speed =
+                response[0].clamp(-self.config.sim_speed_accel, self.config.sim_speed_accel);
 
-            let rotation = response[1].clamp(-ROTATION_ACCEL, ROTATION_ACCEL);
+            let rotation = response[1].clamp(
+                -self.config.sim_rotation_accel,
+                self.config.sim_rotation_accel,
+            );
 
-            animal.speed = (animal.speed + speed).clamp(SPEED_MIN, SPEED_MAX);
-            animal.rotation = nalgebra::Rotation2::new(animal.rotation.angle() + rotation);
+            animal.speed =
+                (animal.speed + speed).clamp(self.config.sim_speed_min, self.config.sim_speed_max);
+            animal.rotation = math::rotation(math::angle(&animal.rotation) + rotation);
         }
     }
 
-    fn evolve(&mut self, rng: &mut dyn RngCore) -> genetic_algorithm::Statistics {
+    fn evolve(&mut self) -> genetic_algorithm::Statistics {
         self.age = 0;
 
         let current_population: Vec<_> = self
             .world
             .animals
             .iter()
-            .map(|animal| AnimalIndividial::from_animal(animal))
+            .map(AnimalIndividial::from_animal)
             .collect();
 
+        let rng = &mut self.streams.evolution;
+
         let (evolved_population, stats) = self.ga.evolve(rng, &current_population);
 
         self.world.animals = evolved_population
             .into_iter()
-            .map(|individual| individual.into_animal(rng))
+            .map(|individual| individual.into_animal(&self.config, rng))
             .collect();
 
         for food in &mut self.world.foods {
-            food.position = rng.gen()
+            food.position =
+                place_food(&self.config, &self.world.food_spots, &mut self.streams.food);
         }
 
         stats
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn positions(simulation: &Simulation) -> Vec<(f32, f32, f32)> {
+        simulation
+            .world()
+            .animals()
+            .iter()
+            .map(|animal| {
+                (
+                    animal.position().x,
+                    animal.position().y,
+                    animal.rotation().angle(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_reproduces_the_run() {
+        let mut a = Simulation::from_seed(42);
+        let mut b = Simulation::from_seed(42);
+
+        for _ in 0..100 {
+            a.step();
+            b.step();
+        }
+
+        let stats_a = a.evolve();
+        let stats_b = b.evolve();
+
+        assert_eq!(stats_a.max_fitness(), stats_b.max_fitness());
+        assert_eq!(stats_a.avg_fitness(), stats_b.avg_fitness());
+
+        for _ in 0..100 {
+            a.step();
+            b.step();
+        }
+
+        assert_eq!(positions(&a), positions(&b));
+    }
+
+    /// Pins the outcome of a run bit for bit; it has to hold on every target
+    /// (see the crate's docs).
+    #[test]
+    fn same_seed_has_a_fixed_checksum() {
+        let mut simulation = Simulation::from_seed(42);
+
+        for _ in 0..500 {
+            simulation.step();
+        }
+
+        let checksum = simulation
+            .world()
+            .animals()
+            .iter()
+            .flat_map(|animal| {
+                let position = animal.position();
+                let rotation = animal.rotation();
+
+                [position.x, position.y, rotation.matrix()[(1, 0)]]
+            })
+            .fold(0u64, |checksum, value| {
+                checksum
+                    .wrapping_mul(31)
+                    .wrapping_add(value.to_bits() as u64)
+            });
+
+        assert_eq!(checksum, 17561043861556570402);
+    }
+
+    #[test]
+    fn from_config() {
+        let config = Config {
+            eye_cells: 5,
+            world_animals: 3,
+            world_foods: 7,
+            // Lets every animal eat, so that there's some fitness to select
+            // by despite the short generation
+            food_size: 2.0,
+            sim_generation_length: 10,
+            ..Config::default()
+        };
+
+        let mut simulation = Simulation::from_config(config.clone(), 42);
+
+        assert_eq!(simulation.config(), &config);
+        assert_eq!(simulation.world().animals().len(), 3);
+        assert_eq!(simulation.world().foods().len(), 7);
+
+        let steps = std::iter::from_fn(|| Some(simulation.step()))
+            .position(|stats| stats.is_some())
+            .unwrap();
+
+        assert_eq!(steps, 10);
+        assert_eq!(simulation.world().animals().len(), 3);
+
+        for animal in simulation.world().animals() {
+            assert_eq!(animal.brain().saliency(&[0.0; 5]).len(), 5);
+
+            let vision = animal.eye().process_vision(
+                animal.position(),
+                animal.rotation(),
+                simulation.world().foods(),
+                &mut ChaCha8Rng::from_seed(Default::default()),
+            );
+
+            let trace = animal.brain().trace(&vision);
+
+            assert_eq!(trace.layers.len(), 1 + config.brain_hidden.len());
+            assert_eq!(trace.outputs().len(), 2);
+            assert_eq!(animal.brain().trace(&vision), trace);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_configs() {
+        let config = Config {
+            food_placement: FoodPlacement::Clusters {
+                count: 0,
+                radius: 0.1,
+            },
+            ..Config::default()
+        };
+
+        assert!(matches!(
+            Simulation::try_from_config(config, 42),
+            Err(scenario::ScenarioError::Invalid { key, .. }) if key == "world.food_placement"
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid `world.width`")]
+    fn panics_on_invalid_configs() {
+        Simulation::from_config(
+            Config {
+                world_width: f32::NAN,
+                ..Config::default()
+            },
+            42,
+        );
+    }
+
+    #[test]
+    fn food_clusters() {
+        let config = Config {
+            world_width: 2.0,
+            food_size: 2.0,
+            food_placement: FoodPlacement::Clusters {
+                count: 1,
+                radius: 0.1,
+            },
+            ..Config::default()
+        };
+
+        let mut simulation = Simulation::from_config(config, 42);
+        let spot = simulation.world.food_spots[0];
+
+        for _ in 0..3 {
+            for food in simulation.world().foods() {
+                let dx = (food.position().x - spot.x).abs();
+                let dy = (food.position().y - spot.y).abs();
+
+                // The cluster may wrap around the world's edges
+                assert!(dx.min(2.0 - dx).hypot(dy.min(1.0 - dy)) <= 0.1 + 1e-5);
+            }
+
+            simulation.step();
+        }
+    }
+
+    #[test]
+    fn different_seeds_diverge() {
+        let a = Simulation::from_seed(1);
+        let b = Simulation::from_seed(2);
+
+        assert_ne!(positions(&a), positions(&b));
+    }
+}
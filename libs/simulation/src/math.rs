@@ -0,0 +1,41 @@
+//! Trigonometry through `libm` instead of `std`, whose functions defer to
+//! the platform's math library - so that native and wasm32 builds compute
+//! bit-for-bit the same rotations (see the crate's docs).
+
+use std::f32::consts::TAU;
+
+use nalgebra::Matrix2;
+
+use crate::*;
+
+pub(crate) fn rotation(angle: f32) -> Rotation2<f32> {
+    let (sin, cos) = libm::sincosf(angle);
+
+    Rotation2::from_matrix_unchecked(Matrix2::new(cos, -sin, sin, cos))
+}
+
+/// Inverse of `rotation`, within `[-PI, PI]`.
+pub(crate) fn angle(rotation: &Rotation2<f32>) -> f32 {
+    let matrix = rotation.matrix();
+
+    libm::atan2f(matrix[(1, 0)], matrix[(0, 0)])
+}
+
+pub(crate) fn random_rotation(rng: &mut dyn RngCore) -> Rotation2<f32> {
+    rotation(rng.gen_range(0.0..TAU))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_nalgebra() {
+        for radians in [-3.0, -1.0, 0.0, 0.5, 2.0, 3.1] {
+            let expected = Rotation2::new(radians);
+
+            assert!((rotation(radians).matrix() - expected.matrix()).amax() < 1e-6);
+            assert!((angle(&rotation(radians)) - expected.angle()).abs() < 1e-6);
+        }
+    }
+}
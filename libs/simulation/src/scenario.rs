@@ -0,0 +1,679 @@
+//! Scenario files - simulation setups (see `Config`) stored as TOML or RON,
+//! so that they can be shared and reviewed like any other text:
+//!
+//! ```toml
+//! [world]
+//! animals = 20
+//! food_placement = { Clusters = { count = 3, radius = 0.1 } }
+//!
+//! [eye]
+//! cells = 13
+//!
+//! [[brain.hidden]]
+//! neurons = 26
+//! kind = "Recurrent"
+//! initializer = "HeUniform"
+//!
+//! [ga]
+//! mut_chance = 0.05
+//! ```
+//!
+//! Sections are `world`, `eye`, `brain`, `ga` and `sim`, with keys named
+//! after `Config`'s fields (e.g. `eye.fov_range` is `eye_fov_range`, while
+//! `world.food_size` is just `food_size`); whatever's omitted takes its
+//! default value (`brain.hidden` follows `eye.cells` then, see
+//! `Config::default_brain_hidden`), while unknown keys are rejected.
+//!
+//! `Config::load` picks the format by the file's extension (`.toml` or
+//! `.ron`).
+
+use std::path::{Path, PathBuf};
+use std::{error, fmt, fs, io};
+
+use neural_network::layer_topology::LayerTopology;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    /// The file is neither `.toml` nor `.ron`.
+    UnknownExtension(PathBuf),
+    Toml(toml::de::Error),
+    Ron(ron::error::SpannedError),
+    /// The value under `key` (e.g. `eye.cells` or `brain.hidden[0]`) is
+    /// out of its allowed range.
+    Invalid {
+        key: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "couldn't read scenario: {err}"),
+            Self::UnknownExtension(path) => write!(
+                f,
+                "unknown scenario format: {} (expected `.toml` or `.ron`)",
+                path.display()
+            ),
+            Self::Toml(err) => write!(f, "couldn't parse scenario: {err}"),
+            Self::Ron(err) => write!(f, "couldn't parse scenario: {err}"),
+            Self::Invalid { key, reason } => write!(f, "invalid `{key}`: {reason}"),
+        }
+    }
+}
+
+impl error::Error for ScenarioError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Toml(err) => Some(err),
+            Self::Ron(err) => Some(err),
+            Self::UnknownExtension(_) | Self::Invalid { .. } => None,
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let path = path.as_ref();
+
+        let parse = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml,
+            Some("ron") => Self::from_ron,
+            _ => return Err(ScenarioError::UnknownExtension(path.into())),
+        };
+
+        parse(&fs::read_to_string(path).map_err(ScenarioError::Io)?)
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self, ScenarioError> {
+        let scenario: Scenario = toml::from_str(toml).map_err(ScenarioError::Toml)?;
+
+        scenario.into_config()
+    }
+
+    pub fn from_ron(ron: &str) -> Result<Self, ScenarioError> {
+        let scenario: Scenario = ron::from_str(ron).map_err(ScenarioError::Ron)?;
+
+        scenario.into_config()
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(&Scenario::from(self.clone())).unwrap()
+    }
+
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(&Scenario::from(self.clone()), Default::default()).unwrap()
+    }
+
+    /// Checks that the simulation can run with this config; the error
+    /// points at the offending key, as named in scenario files.
+    pub fn validate(&self) -> Result<(), ScenarioError> {
+        let positive = |key: &str, value: f32| {
+            check(key, value.is_finite() && value > 0.0, "has to be positive")
+        };
+
+        let non_negative = |key: &str, value: f32| {
+            check(key, value.is_finite() && value >= 0.0, "can't be negative")
+        };
+
+        positive("world.width", self.world_width)?;
+        positive("world.height", self.world_height)?;
+        check(
+            "world.animals",
+            self.world_animals > 0,
+            "has to be positive",
+        )?;
+        non_negative("world.food_size", self.food_size)?;
+
+        if let FoodPlacement::Clusters { count, radius } = self.food_placement {
+            check(
+                "world.food_placement",
+                count > 0,
+                "needs at least one cluster",
+            )?;
+            non_negative("world.food_placement", radius)?;
+        }
+
+        positive("eye.fov_range", self.eye_fov_range)?;
+        positive("eye.fov_angle", self.eye_fov_angle)?;
+
+        check(
+            "eye.fov_angle",
+            self.eye_fov_angle <= std::f32::consts::TAU,
+            "can't exceed a full turn",
+        )?;
+
+        check("eye.cells", self.eye_cells > 0, "has to be positive")?;
+        non_negative("eye.noise", self.eye_noise)?;
+        self.validate_brain()?;
+
+        check(
+            "ga.mut_chance",
+            (0.0..=1.0).contains(&self.ga_mut_chance),
+            "has to be within [0, 1]",
+        )?;
+
+        non_negative("ga.mut_coeff", self.ga_mut_coeff)?;
+
+        check(
+            "ga.hall_of_fame_capacity",
+            self.ga_hall_of_fame_capacity > 0,
+            "has to be positive",
+        )?;
+
+        non_negative("ga.hall_of_fame_distance", self.ga_hall_of_fame_distance)?;
+        non_negative("sim.speed_min", self.sim_speed_min)?;
+        non_negative("sim.speed_max", self.sim_speed_max)?;
+
+        check(
+            "sim.speed_max",
+            self.sim_speed_min <= self.sim_speed_max,
+            "can't be lower than `sim.speed_min`",
+        )?;
+
+        non_negative("sim.speed_accel", self.sim_speed_accel)?;
+        non_negative("sim.rotation_accel", self.sim_rotation_accel)?;
+
+        check(
+            "sim.generation_length",
+            self.sim_generation_length > 0,
+            "has to be positive",
+        )
+    }
+
+    fn validate_brain(&self) -> Result<(), ScenarioError> {
+        use neural_network::{Error, Network};
+
+        let Err(err) = Network::validate_topology(&Brain::topology(self)) else {
+            return Ok(());
+        };
+
+        let layer = match err {
+            Error::EmptyLayer { layer }
+            | Error::LayerSizeMismatch { layer, .. }
+            | Error::InvalidLayer { layer, .. }
+            | Error::TooManyWeights { layer } => Some(layer),
+            _ => None,
+        };
+
+        let key = match layer {
+            Some(0) => "eye.cells".into(),
+            Some(layer) if layer <= self.brain_hidden.len() => {
+                format!("brain.hidden[{}]", layer - 1)
+            }
+            _ => "brain.hidden".into(),
+        };
+
+        Err(ScenarioError::Invalid {
+            key,
+            reason: err.to_string(),
+        })
+    }
+}
+
+fn check(key: &str, valid: bool, reason: &str) -> Result<(), ScenarioError> {
+    if valid {
+        Ok(())
+    } else {
+        Err(ScenarioError::Invalid {
+            key: key.into(),
+            reason: reason.into(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Scenario {
+    world: WorldSection,
+    eye: EyeSection,
+    brain: BrainSection,
+    ga: GaSection,
+    sim: SimSection,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct WorldSection {
+    width: f32,
+    height: f32,
+    animals: usize,
+    foods: usize,
+    food_size: f32,
+    food_placement: FoodPlacement,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct EyeSection {
+    fov_range: f32,
+    fov_angle: f32,
+    cells: usize,
+    noise: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct BrainSection {
+    /// When missing, follows the scenario's `eye.cells` (see
+    /// `Config::default_brain_hidden`).
+    #[serde(with = "present", skip_serializing_if = "Option::is_none")]
+    hidden: Option<Vec<LayerTopology>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct GaSection {
+    selection: Selection,
+    crossover: Crossover,
+    mutation: Mutation,
+    mut_chance: f32,
+    mut_coeff: f32,
+    hall_of_fame_capacity: usize,
+    hall_of_fame_distance: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SimSection {
+    speed_min: f32,
+    speed_max: f32,
+    speed_accel: f32,
+    rotation_accel: f32,
+    generation_length: usize,
+}
+
+impl Scenario {
+    fn into_config(self) -> Result<Config, ScenarioError> {
+        let Self {
+            world,
+            eye,
+            brain,
+            ga,
+            sim,
+        } = self;
+
+        let config = Config {
+            eye_fov_range: eye.fov_range,
+            eye_fov_angle: eye.fov_angle,
+            eye_cells: eye.cells,
+            eye_noise: eye.noise,
+
+            brain_hidden: brain
+                .hidden
+                .unwrap_or_else(|| Config::default_brain_hidden(eye.cells)),
+
+            world_width: world.width,
+            world_height: world.height,
+            world_animals: world.animals,
+            world_foods: world.foods,
+            food_size: world.food_size,
+            food_placement: world.food_placement,
+
+            ga_selection: ga.selection,
+            ga_crossover: ga.crossover,
+            ga_mutation: ga.mutation,
+            ga_mut_chance: ga.mut_chance,
+            ga_mut_coeff: ga.mut_coeff,
+            ga_hall_of_fame_capacity: ga.hall_of_fame_capacity,
+            ga_hall_of_fame_distance: ga.hall_of_fame_distance,
+
+            sim_speed_min: sim.speed_min,
+            sim_speed_max: sim.speed_max,
+            sim_speed_accel: sim.speed_accel,
+            sim_rotation_accel: sim.rotation_accel,
+            sim_generation_length: sim.generation_length,
+        };
+
+        config.validate()?;
+
+        Ok(config)
+    }
+}
+
+impl From<Config> for Scenario {
+    fn from(config: Config) -> Self {
+        Self {
+            world: WorldSection {
+                width: config.world_width,
+                height: config.world_height,
+                animals: config.world_animals,
+                foods: config.world_foods,
+                food_size: config.food_size,
+                food_placement: config.food_placement,
+            },
+            eye: EyeSection {
+                fov_range: config.eye_fov_range,
+                fov_angle: config.eye_fov_angle,
+                cells: config.eye_cells,
+                noise: config.eye_noise,
+            },
+            brain: BrainSection {
+                hidden: Some(config.brain_hidden),
+            },
+            ga: GaSection {
+                selection: config.ga_selection,
+                crossover: config.ga_crossover,
+                mutation: config.ga_mutation,
+                mut_chance: config.ga_mut_chance,
+                mut_coeff: config.ga_mut_coeff,
+                hall_of_fame_capacity: config.ga_hall_of_fame_capacity,
+                hall_of_fame_distance: config.ga_hall_of_fame_distance,
+            },
+            sim: SimSection {
+                speed_min: config.sim_speed_min,
+                speed_max: config.sim_speed_max,
+                speed_accel: config.sim_speed_accel,
+                rotation_accel: config.sim_rotation_accel,
+                generation_length: config.sim_generation_length,
+            },
+        }
+    }
+}
+
+// Missing keys fall back to `Config::default()`, except for `brain.hidden`
+
+impl Default for Scenario {
+    fn default() -> Self {
+        let mut scenario = Self::from(Config::default());
+
+        scenario.brain.hidden = None;
+        scenario
+    }
+}
+
+impl Default for WorldSection {
+    fn default() -> Self {
+        Scenario::default().world
+    }
+}
+
+impl Default for EyeSection {
+    fn default() -> Self {
+        Scenario::default().eye
+    }
+}
+
+impl Default for BrainSection {
+    fn default() -> Self {
+        Scenario::default().brain
+    }
+}
+
+impl Default for GaSection {
+    fn default() -> Self {
+        Scenario::default().ga
+    }
+}
+
+impl Default for SimSection {
+    fn default() -> Self {
+        Scenario::default().sim
+    }
+}
+
+/// (De)serializes `Option`s as their values, so that files don't have to
+/// spell out `Some(...)` (which RON would require otherwise); `None` is
+/// expected to be skipped.
+mod present {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match value {
+            Some(value) => value.serialize(serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neural_network::layer_topology::LayerKind;
+
+    fn invalid_key(result: Result<Config, ScenarioError>) -> String {
+        match result {
+            Err(ScenarioError::Invalid { key, .. }) => key,
+            result => panic!("expected a validation error, got {result:?}"),
+        }
+    }
+
+    mod load {
+        use super::*;
+
+        /// Temporary directory for scenario files, removed once dropped.
+        struct ScenarioDir(PathBuf);
+
+        impl ScenarioDir {
+            fn new(test: &str) -> Self {
+                let path =
+                    std::env::temp_dir().join(format!("scenario-{}-{test}", std::process::id()));
+
+                fs::create_dir_all(&path).unwrap();
+                Self(path)
+            }
+
+            /// Writes `contents` to a fresh file with given name.
+            fn file(&self, name: &str, contents: &str) -> PathBuf {
+                let path = self.0.join(name);
+
+                fs::write(&path, contents).unwrap();
+                path
+            }
+        }
+
+        impl Drop for ScenarioDir {
+            fn drop(&mut self) {
+                let _ = fs::remove_dir_all(&self.0);
+            }
+        }
+
+        #[test]
+        fn test() {
+            let dir = ScenarioDir::new("test");
+            let toml = dir.file("test.toml", "[eye]\ncells = 13");
+            let ron = dir.file("test.ron", "(eye: (cells: 13))");
+
+            let expected = Config {
+                eye_cells: 13,
+                brain_hidden: Config::default_brain_hidden(13),
+                ..Config::default()
+            };
+
+            assert_eq!(Config::load(toml).unwrap(), expected);
+            assert_eq!(Config::load(ron).unwrap(), expected);
+        }
+
+        #[test]
+        fn rejects_unknown_extensions() {
+            let dir = ScenarioDir::new("rejects_unknown_extensions");
+            let path = dir.file("test.json", "{}");
+
+            assert!(matches!(
+                Config::load(path),
+                Err(ScenarioError::UnknownExtension(_))
+            ));
+        }
+
+        #[test]
+        fn reports_missing_files() {
+            assert!(matches!(
+                Config::load("missing.toml"),
+                Err(ScenarioError::Io(err)) if err.kind() == io::ErrorKind::NotFound
+            ));
+        }
+    }
+
+    mod from_toml {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = Config::from_toml(
+                r#"
+                [world]
+                animals = 20
+                food_placement = { Clusters = { count = 3, radius = 0.1 } }
+
+                [eye]
+                cells = 13
+
+                [[brain.hidden]]
+                neurons = 26
+                kind = "Recurrent"
+
+                [ga]
+                mut_chance = 0.05
+                "#,
+            )
+            .unwrap();
+
+            assert_eq!(
+                config,
+                Config {
+                    world_animals: 20,
+                    food_placement: FoodPlacement::Clusters {
+                        count: 3,
+                        radius: 0.1
+                    },
+                    eye_cells: 13,
+                    brain_hidden: vec![LayerTopology::new(26).with_kind(LayerKind::Recurrent)],
+                    ga_mut_chance: 0.05,
+                    ..Config::default()
+                }
+            );
+        }
+
+        #[test]
+        fn empty() {
+            assert_eq!(Config::from_toml("").unwrap(), Config::default());
+        }
+
+        #[test]
+        fn derives_hidden_layers_from_eye_cells() {
+            let config = Config::from_toml("[eye]\ncells = 5").unwrap();
+
+            assert_eq!(config.brain_hidden, Config::default_brain_hidden(5));
+            assert_eq!(config.brain_hidden[0].neurons, 10);
+
+            let config = Config::from_toml("[eye]\ncells = 5\n\n[brain]\nhidden = []").unwrap();
+
+            assert!(config.brain_hidden.is_empty());
+        }
+
+        #[test]
+        fn rejects_unknown_keys() {
+            let err = Config::from_toml("[eye]\ncels = 13").unwrap_err();
+
+            assert!(matches!(err, ScenarioError::Toml(_)));
+            assert!(err.to_string().contains("cels"));
+        }
+
+        #[test]
+        fn rejects_invalid_values() {
+            assert_eq!(
+                invalid_key(Config::from_toml("[eye]\ncells = 0")),
+                "eye.cells"
+            );
+
+            assert_eq!(
+                invalid_key(Config::from_toml("[ga]\nmut_chance = 1.5")),
+                "ga.mut_chance"
+            );
+
+            assert_eq!(
+                invalid_key(Config::from_toml(
+                    "[sim]\nspeed_min = 0.01\nspeed_max = 0.001"
+                )),
+                "sim.speed_max"
+            );
+
+            assert_eq!(
+                invalid_key(Config::from_toml(
+                    "[[brain.hidden]]\nneurons = 4\n\n[[brain.hidden]]\nneurons = 0"
+                )),
+                "brain.hidden[1]"
+            );
+
+            assert_eq!(
+                invalid_key(Config::from_toml(
+                    "[[brain.hidden]]\nneurons = 4\nkind = \"Softmax\""
+                )),
+                "brain.hidden[0]"
+            );
+
+            assert_eq!(
+                invalid_key(Config::from_toml(
+                    "[brain]\nhidden = [{ neurons = 4611686018427387904 }]"
+                )),
+                "brain.hidden[0]"
+            );
+        }
+    }
+
+    mod from_ron {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = Config::from_ron(
+                "(world: (width: 2.0, food_placement: Uniform), sim: (generation_length: 100))",
+            )
+            .unwrap();
+
+            assert_eq!(
+                config,
+                Config {
+                    world_width: 2.0,
+                    sim_generation_length: 100,
+                    ..Config::default()
+                }
+            );
+
+            assert_eq!(
+                invalid_key(Config::from_ron("(world: (height: -1.0))")),
+                "world.height"
+            );
+        }
+    }
+
+    mod round_trip {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = Config {
+                world_width: 2.5,
+                food_placement: FoodPlacement::Clusters {
+                    count: 2,
+                    radius: 0.2,
+                },
+                brain_hidden: vec![
+                    LayerTopology::new(8).with_kind(LayerKind::Gru),
+                    LayerTopology::new(4),
+                ],
+                ..Config::default()
+            };
+
+            assert_eq!(Config::from_toml(&config.to_toml()).unwrap(), config);
+            assert_eq!(Config::from_ron(&config.to_ron()).unwrap(), config);
+        }
+    }
+}
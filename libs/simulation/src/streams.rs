@@ -0,0 +1,31 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Independent random number streams of a single simulation.
+///
+/// All of them are derived from the same seed, but each has its own ChaCha
+/// stream, so e.g. enabling vision noise doesn't change where the food is
+/// going to respawn.
+pub(crate) struct Streams {
+    pub(crate) world: ChaCha8Rng,
+    pub(crate) vision: ChaCha8Rng,
+    pub(crate) food: ChaCha8Rng,
+    pub(crate) evolution: ChaCha8Rng,
+}
+
+impl Streams {
+    pub(crate) fn new(seed: u64) -> Self {
+        let stream = |stream| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            rng.set_stream(stream);
+            rng
+        };
+
+        Self {
+            world: stream(0),
+            vision: stream(1),
+            food: stream(2),
+            evolution: stream(3),
+        }
+    }
+}
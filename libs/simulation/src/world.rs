@@ -3,14 +3,35 @@ use crate::*;
 pub struct World {
     pub(crate) animals: Vec<Animal>,
     pub(crate) foods: Vec<Food>,
+    /// Centers of the food clusters; empty unless food is placed in
+    /// clusters.
+    pub(crate) food_spots: Vec<Point2<f32>>,
 }
 
 impl World {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let animals = (0..40).map(|_| Animal::random(rng)).collect();
-        let foods = (0..40).map(|_| Food::random(rng)).collect();
+    pub fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        let animals = (0..config.world_animals)
+            .map(|_| Animal::random(config, rng))
+            .collect();
 
-        Self { animals, foods }
+        let food_spots = match config.food_placement {
+            FoodPlacement::Uniform => Vec::new(),
+            FoodPlacement::Clusters { count, .. } => {
+                (0..count).map(|_| config.random_position(rng)).collect()
+            }
+        };
+
+        let foods = (0..config.world_foods)
+            .map(|_| Food {
+                position: place_food(config, &food_spots, rng),
+            })
+            .collect();
+
+        Self {
+            animals,
+            foods,
+            food_spots,
+        }
     }
 
     pub fn animals(&self) -> &[Animal] {
@@ -21,3 +42,28 @@ impl World {
         &self.foods
     }
 }
+
+/// Picks a position for a piece of food, following `config.food_placement`.
+pub(crate) fn place_food(
+    config: &Config,
+    spots: &[Point2<f32>],
+    rng: &mut dyn RngCore,
+) -> Point2<f32> {
+    match config.food_placement {
+        FoodPlacement::Uniform => config.random_position(rng),
+
+        FoodPlacement::Clusters { radius, .. } => {
+            let spot = spots[rng.gen_range(0..spots.len())];
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+
+            // Square root keeps the food evenly spread over the disc
+            let distance = radius * rng.gen::<f32>().sqrt();
+            let (sin, cos) = libm::sincosf(angle);
+
+            Point2::new(
+                nalgebra::wrap(spot.x + distance * cos, 0.0, config.world_width),
+                nalgebra::wrap(spot.y + distance * sin, 0.0, config.world_height),
+            )
+        }
+    }
+}
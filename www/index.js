@@ -1,6 +1,12 @@
 import * as sim from "simulation-wasm";
 
-const simulation = new sim.Simulation();
+// Pass `?seed=<u64>` to replay a session; the seed in use is always logged
+const params = new URLSearchParams(window.location.search);
+const seed = params.has('seed') ? parseSeed(params.get('seed')) : undefined;
+
+const simulation = new sim.Simulation(seed);
+
+console.log(`seed=${simulation.seed()}`);
 
 document.getElementById('train').onclick = function () {
     console.log(simulation.train());
@@ -51,4 +57,14 @@ function redraw() {
     requestAnimationFrame(redraw);
 }
 
-redraw();
\ No newline at end of file
+redraw();
+
+// Returns `undefined` (i.e. a random seed) for anything that's not a u64
+function parseSeed(value) {
+    if (/^[0-9]+$/.test(value) && BigInt(value) < 2n ** 64n) {
+        return BigInt(value);
+    }
+
+    console.warn(`ignoring invalid seed: ${value}`);
+    return undefined;
+}